@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 #![feature(type_alias_impl_trait, const_async_blocks)]
 #![warn(
     clippy::complexity,
@@ -9,19 +9,53 @@
     rust_2018_idioms
 )]
 
+use arrayvec::{ArrayString, ArrayVec};
 use asr::{
     file_format::pe::{self, MachineType},
     future::{next_tick, retry},
-    settings::Gui,
-    signature::Signature,
+    settings::{self, gui::Title, Gui},
+    time::Duration,
     timer::{self, TimerState},
     watcher::Watcher,
-    Address, Address32, Process,
+    Address, Process,
 };
+use core::{fmt::Write, ops::RangeInclusive};
+use preset::Preset;
+use route::Route;
+use signatures::{Layout, Modules, PendingScan, Resolved, Scanner};
 
+#[cfg(not(test))]
 asr::panic_handler!();
+#[cfg(not(test))]
 asr::async_main!(nightly);
 
+mod preset;
+mod route;
+mod runtime;
+mod signatures;
+#[cfg(test)]
+mod tests;
+mod trace;
+
+/// Scene IDs of the Special Stages (UFO stages), listed right after the Encore mode acts in the game's scene list
+const SPECIAL_STAGES: RangeInclusive<u8> = 65..=71;
+
+/// Scene IDs of the 32 Blue Spheres bonus stages, listed right after the Special Stages
+const BONUS_STAGES: RangeInclusive<u8> = 72..=103;
+
+/// Number of consecutive ticks (about 4 seconds) the act timer needs to be stopped for, with the game running,
+/// before we consider the act as cleared where the results screen can't be read. Dying stops the timer too, but
+/// the act gets reloaded once the death animation and the fade out are over (about 2 seconds), while the results
+/// screen alone lasts longer than this.
+const ACT_CLEAR_TICKS: u16 = 480;
+
+/// Going back to the previous act within this many ticks (about 5 seconds) means the last split was a false one
+const FALSE_SPLIT_TICKS: u16 = 600;
+
+/// Looking up Game.dll is too expensive to do on every tick, so it's only checked once every this many ticks
+/// (about 1 second)
+const GAME_DLL_CHECK_TICKS: u8 = 120;
+
 const PROCESS_NAMES: &[&str] = &[
     "SonicMania.exe",
     "RSDKv5.exe",
@@ -38,8 +72,11 @@ const PROCESS_NAMES: &[&str] = &[
     "RSDKv5U-dx9_64.exe",
 ];
 
+#[cfg_attr(test, allow(dead_code))]
 async fn main() {
     let mut settings = Settings::register();
+    // Presets are only applied when picked, so the settings can still be adjusted afterwards
+    let mut preset = settings.preset;
 
     loop {
         // Hook to the target process
@@ -49,9 +86,16 @@ async fn main() {
             .until_closes(async {
                 // Once the target has been found and attached to, set up some default watchers
                 let mut watchers = Watchers::default();
+                let mut game_time = GameTime::default();
+                let mut load_removal = LoadRemoval::default();
+                let mut character_log = CharacterLog::default();
+                let mut death_counter = DeathCounter::default();
+                let mut split_check = SplitCheck::default();
+                let mut trace_recorder = trace::Recorder::default();
 
                 // Perform memory scanning to look for the addresses we need
-                let addresses = Addresses::init(&process).await;
+                let mut addresses = Addresses::init(&process).await;
+                addresses.report();
 
                 loop {
                     // Splitting logic. Adapted from OG LiveSplit:
@@ -61,18 +105,70 @@ async fn main() {
                     // 3. If reset does not return true, then the split action will be run.
                     // 4. If the timer is currently not running (and not paused), then the start action will be run.
                     settings.update();
-                    update_loop(&process, &addresses, &mut watchers);
+                    if settings.preset != preset {
+                        preset = settings.preset;
+                        let map = settings::Map::load();
+                        preset.apply(&map);
+                        map.store();
+                        settings.update_from(&map);
+                    }
+
+                    match addresses.update_game_module(&process) {
+                        GameModule::Unchanged => {}
+                        GameModule::Reloaded => addresses.report(),
+                        GameModule::Scanning | GameModule::Unloaded => {
+                            // Nothing to read until Game.dll gets loaded again and scanned
+                            next_tick().await;
+                            continue;
+                        }
+                    }
+
+                    let snapshot = Snapshot::read(&process, &addresses);
+                    if settings.debug_trace {
+                        trace_recorder.record(&snapshot);
+                    }
+                    let first_update = watchers.level_id.pair.is_none();
+                    watchers.update(&snapshot);
+                    game_time.update(&watchers);
+                    load_removal.update(&watchers);
+                    character_log.update(&watchers);
+                    death_counter.update(&watchers);
+                    update_stats(&watchers, first_update);
 
                     let timer_state = timer::state();
+                    split_check.update(timer_state);
                     if timer_state == TimerState::Running || timer_state == TimerState::Paused {
+                        // In Time Attack, the result reported by the game is always the one that matters
+                        let time_attack = matches!(
+                            &watchers.game_mode.pair,
+                            Some(mode) if mode.current == GameMode::TimeAttack
+                        );
+
+                        if settings.timing_method == TimingMethod::InGameTime || time_attack {
+                            timer::pause_game_time();
+                            timer::set_game_time(game_time.total());
+                        } else if load_removal.is_loading() {
+                            timer::pause_game_time()
+                        } else {
+                            timer::resume_game_time()
+                        }
+
                         if reset(&watchers, &settings) {
                             timer::reset()
+                        } else if undo_split(&mut watchers, &settings) {
+                            timer::undo_split();
+                            split_check.undo();
                         } else if split(&watchers, &settings) {
-                            timer::split()
+                            timer::split();
+                            split_check.split();
                         }
                     }
 
                     if timer::state() == TimerState::NotRunning && start(&watchers, &settings) {
+                        game_time.reset();
+                        character_log.reset();
+                        death_counter.reset();
+                        split_check.start(&watchers, &settings);
                         timer::start();
                     }
 
@@ -87,13 +183,46 @@ async fn main() {
 struct Watchers {
     level_id_numeric: Watcher<u8>,
     level_id: Watcher<Acts>,
+    /// Position along the route of the furthest act reached in the current run
+    furthest_act: u8,
+    /// Whether `furthest_act` moved forward on this tick
+    progressed: bool,
+    /// Whether the act left when `furthest_act` last moved forward had been cleared
+    left_cleared_act: bool,
+    ticks_since_progress: u16,
+    /// Whether we just went back to the act left less than `FALSE_SPLIT_TICKS` ago
+    went_back: bool,
+    /// Acts played in the current run. On the any order route, only the ones not played yet count as progress.
+    played_acts: ArrayVec<Acts, 64>,
+    /// Whether the act reached on this tick hadn't been played yet in the current run
+    new_act: bool,
+    /// Whether the act being played had been played already in the current run when it was reached
+    replaying_act: bool,
     status: Watcher<u8>,
+    igt: Watcher<Duration>,
+    igt_frozen_ticks: u16,
+    act_clear: Watcher<bool>,
+    /// Whether the act being played has been cleared already, reset when the next act starts
+    act_cleared: Watcher<bool>,
+    boss: Watcher<BossState>,
+    boss_health: Watcher<Option<u8>>,
+    star_post: Watcher<u32>,
+    /// Star posts touched in the current act, in the order they have been touched
+    star_posts_touched: ArrayVec<u32, 32>,
+    star_posts_reached: Watcher<u8>,
+    in_act: Watcher<bool>,
     egg_reverie_health: Watcher<u8>,
     titanic_monarch_2_defeated: Watcher<bool>,
     chaos_emeralds: Watcher<u8>,
+    in_special_stage: Watcher<bool>,
+    emeralds_on_entry: u8,
+    medals: Watcher<Medals>,
+    in_bonus_stage: Watcher<bool>,
+    medals_on_entry: Medals,
     game_mode: Watcher<GameMode>,
-    character_1: Watcher<u8>,
-    character_2: Watcher<u8>,
+    character_1: Watcher<Character>,
+    character_2: Watcher<Character>,
+    stock: Watcher<[Character; 3]>,
     start_trigger: Watcher<u32>,
     mania_mode_selection: Watcher<bool>,
     save_selection_mania: Watcher<u8>,
@@ -109,880 +238,2199 @@ struct Watchers {
     save_encore_1: Watcher<bool>,
     save_encore_2: Watcher<bool>,
     save_encore_3: Watcher<bool>,
+    save_progress: Watcher<SaveProgress>,
 }
 
-#[derive(Gui)]
-struct Settings {
-    #[default = true]
-    /// START: Enable auto start in Mania mode
-    start_mania_mode: bool,
-    #[default = true]
-    /// START: Enable auto start in Encore mode
-    start_encore_mode: bool,
-    #[default = true]
-    /// RESET: Auto reset when returning to the save selection screen
-    reset_save_select: bool,
-    #[default = false]
-    /// RESET: Auto reset when opening the dev menu
-    reset_dev_menu: bool,
-    #[default = true]
-    /// Green Hill Act 1
-    green_hill_1: bool,
-    #[default = true]
-    /// Green Hill Act 2
-    green_hill_2: bool,
-    #[default = true]
-    /// Chemical Plant Act 1
-    chemical_plant_1: bool,
-    #[default = true]
-    /// Chemical Plant Act 2
-    chemical_plant_2: bool,
-    #[default = true]
-    /// Studiopolis Act 1
-    studiopolis_1: bool,
-    #[default = true]
-    /// Studiopolis Act 2
-    studiopolis_2: bool,
-    #[default = true]
-    /// Flying Battery Act 1
-    flying_battery_1: bool,
-    #[default = true]
-    /// Flying Battery Act 2
-    flying_battery_2: bool,
-    #[default = true]
-    /// Press Garden Act 1
-    press_garden_1: bool,
-    #[default = true]
-    /// Press Garden Act 2
-    press_garden_2: bool,
-    #[default = true]
-    /// Stardust Speedway Act 1
-    stardust_speedway_1: bool,
-    #[default = true]
-    /// Stardust Speedway Act 2
-    stardust_speedway_2: bool,
-    #[default = true]
-    /// Hydrocity Act 1
-    hydrocity_1: bool,
-    #[default = true]
-    /// Hydrocity Act 2
-    hydrocity_2: bool,
-    #[default = true]
-    /// Mirage Saloon Act 1
-    mirage_saloon_1: bool,
-    #[default = true]
-    /// Mirage Saloon Act 2
-    mirage_saloon_2: bool,
-    #[default = true]
-    /// Oil Ocean Act 1
-    oil_ocean_1: bool,
-    #[default = true]
-    /// Oil Ocean Act 2
-    oil_ocean_2: bool,
-    #[default = true]
-    /// Lava Reef Act 1
-    lava_reef_1: bool,
-    #[default = true]
-    /// Lava Reef Act 2
-    lava_reef_2: bool,
-    #[default = true]
-    /// Metallic Madness Act 1
-    metallic_madness_1: bool,
-    #[default = true]
-    /// Metallic Madness Act 2
-    metallic_madness_2: bool,
-    #[default = true]
-    /// Titanic Monarch Act 1
-    titanic_monarch_1: bool,
-    #[default = true]
-    /// Titanic Monarch Act 2
-    titanic_monarch_2: bool,
-    #[default = true]
-    /// Egg Reverie
-    egg_reverie: bool,
-    #[default = true]
-    /// ENCORE: Angel Island
-    angel_island: bool,
-    #[default = true]
-    /// ENCORE: Green Hill Act 1
-    encore_green_hill_1: bool,
-    #[default = true]
-    /// ENCORE: Green Hill Act 2
-    encore_green_hill_2: bool,
-    #[default = true]
-    /// ENCORE: Chemical Plant Act 1
-    encore_chemical_plant_1: bool,
-    #[default = true]
-    /// ENCORE: Chemical Plant Act 2,
-    encore_chemical_plant_2: bool,
-    #[default = true]
-    /// ENCORE: Studiopolis Act 1
-    encore_studiopolis_1: bool,
-    #[default = true]
-    /// ENCORE: Studiopolis Act 2
-    encore_studiopolis_2: bool,
-    #[default = true]
-    /// ENCORE: Flying Battery Act 1
-    encore_flying_battery_1: bool,
-    #[default = true]
-    /// ENCORE: Flying Battery Act 2
-    encore_flying_battery_2: bool,
-    #[default = true]
-    /// ENCORE: Press Garden Act 1
-    encore_press_garden_1: bool,
-    #[default = true]
-    /// ENCORE: Press Garden Act 2
-    encore_press_garden_2: bool,
-    #[default = true]
-    /// ENCORE: Stardust Speedway Act 1
-    encore_stardust_speedway_1: bool,
-    #[default = true]
-    /// ENCORE: Stardust Speedway Act 2
-    encore_stardust_speedway_2: bool,
-    #[default = true]
-    /// ENCORE: Hydrocity Act 1
-    encore_hydrocity_1: bool,
-    #[default = true]
-    /// ENCORE: Hydrocity Act 2
-    encore_hydrocity_2: bool,
-    #[default = true]
-    /// ENCORE: Mirage Saloon Act 1
-    encore_mirage_saloon_1: bool,
-    #[default = true]
-    /// ENCORE: Mirage Saloon Act 2
-    encore_mirage_saloon_2: bool,
-    #[default = true]
-    /// ENCORE: Oil Ocean Act 1
-    encore_oil_ocean_1: bool,
-    #[default = true]
-    /// ENCORE: Oil Ocean Act 2
-    encore_oil_ocean_2: bool,
-    #[default = true]
-    /// ENCORE: Lava Reef Act 1
-    encore_lava_reef_1: bool,
-    #[default = true]
-    /// ENCORE: Lava Reef Act 2
-    encore_lava_reef_2: bool,
-    #[default = true]
-    /// ENCORE: Metallic Madness Act 1
-    encore_metallic_madness_1: bool,
-    #[default = true]
-    /// ENCORE: Metallic Madness Act 2
-    encore_metallic_madness_2: bool,
-    #[default = true]
-    /// ENCORE: Titanic Monarch Act 1
-    encore_titanic_monarch_1: bool,
-    #[default = true]
-    /// ENCORE: Titanic Monarch Act 2
-    encore_titanic_monarch_2: bool,
-}
-
-struct Addresses {
-    is_64_bit: bool,
-    level_id_base: Address,
-    level_id_offset: u32,
-    status_base: Address,
-    status_offset: u32,
-    egg_reverie_base: Address,
-    egg_reverie1_offset1: u32,
-    egg_reverie1_offset2: u32,
-    egg_reverie2_offset1: u32,
-    egg_reverie2_offset2: u32,
-    egg_monarch_base: Address,
-    egg_monarch_offset: u32,
-    chaos_emerald_base: Address,
-    chaos_emerald_offset1: u32,
-    chaos_emerald_offset2: u32,
-    character_base: Address,
-    character_offset: u32,
-    start_trigger: Address,
-    mania_mode_selection: Address,
-    save_selection_mania: Address,
-    save_0: Address,
-    save_1: Address,
-    save_2: Address,
-    save_3: Address,
-    save_4: Address,
-    save_5: Address,
-    save_6: Address,
-    save_7: Address,
-    save_selection_encore: Address,
-    encore_save_1: Address,
-    encore_save_2: Address,
-    encore_save_3: Address,
+/// Keeps track of the in-game time across the whole run.
+/// The game only tracks the time of the act currently being played, so we need
+/// to add up the final time of every act we go through.
+#[derive(Default)]
+struct GameTime {
+    accumulated: Duration,
+    current_act: Duration,
 }
 
-impl Addresses {
-    async fn init(process: &Process) -> Self {
-        let main_module = {
-            let main_module_base = retry(|| {
-                PROCESS_NAMES
-                    .iter()
-                    .find_map(|&name| process.get_module_address(name).ok())
-            })
-            .await;
-            let main_module_size =
-                retry(|| pe::read_size_of_image(process, main_module_base)).await as u64;
-            (main_module_base, main_module_size)
+impl GameTime {
+    fn update(&mut self, watchers: &Watchers) {
+        let Some(level_id) = &watchers.level_id.pair else {
+            return;
+        };
+        let Some(igt) = &watchers.igt.pair else {
+            return;
         };
 
-        let is_64_bit =
-            retry(|| pe::MachineType::read(process, main_module.0)).await == MachineType::X86_64;
-
-        let base: Address;
-        let size: u64;
-        let mut ptr: Address;
-
-        // Try to find the Game.dll module. If it succeeds, it means we are running on the decompilation
-        if let Ok(ubase) = process.get_module_address("Game.dll") {
-            // In the decomp, most of the sigscanning will be performed in the Game.dll module
-            base = ubase;
-            size = retry(|| pe::read_size_of_image(process, base)).await as _;
-        } else {
-            // In the retail version, we need only the main module, which means base and size are
-            // going to be equal the main_module_base and main_module_size.
-            base = main_module.0;
-            size = main_module.1;
-        }
-
-        let is_rsdk_5u: bool;
-        let level_id_base: Address;
-        let level_id_offset: u32;
-        let status_base: Address;
-        let status_offset: u32;
-        let egg_reverie_base: Address;
-        let egg_reverie1_offset1: u32;
-        let egg_reverie1_offset2: u32;
-        let egg_reverie2_offset1: u32;
-        let egg_reverie2_offset2: u32;
-        let egg_monarch_base: Address;
-        let egg_monarch_offset: u32;
-        let chaos_emerald_base: Address;
-        let chaos_emerald_offset1: u32;
-        let chaos_emerald_offset2: u32;
-        let character_base: Address;
-        let character_offset: u32;
-        let start_trigger: Address;
-        let mania_mode_selection: Address;
-        let save_selection_mania: Address;
-        let save_0: Address;
-        let save_1: Address;
-        let save_2: Address;
-        let save_3: Address;
-        let save_4: Address;
-        let save_5: Address;
-        let save_6: Address;
-        let save_7: Address;
-        let save_selection_encore: Address;
-        let encore_save_1: Address;
-        let encore_save_2: Address;
-        let encore_save_3: Address;
-
-        // 32-bit version
-        if !is_64_bit {
-            // Detect if we're on rsdk5U or not. It alters some of the offsets later.
-            // The retail version of the game is NEVER rsdk5U
-            const SIGRSDK5U: Signature<24> =
-                Signature::new("3D ???????? 0F 87 ???????? FF 24 85 ???????? A1 ???????? 89");
-            is_rsdk_5u = SIGRSDK5U.scan_process_range(process, main_module).is_some();
-
-            // LevelID. Actually a SceneID, it also identifies whenever we are in the main menu
-            const SIG: Signature<14> = Signature::new("8B ?? ???????? 85 C9 74 1E 80 ?? ?? 03");
-            ptr = retry(|| SIG.scan_process_range(process, (base, size))).await + 2;
-            level_id_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            level_id_offset = retry(|| process.read::<u8>(ptr + 28)).await as u32;
-
-            // Status and in-game timer. Doesn't even need an explanation, though we are not gonna use it directly
-            const SIGSTATUS: Signature<16> =
-                Signature::new("A1 ???????? 80 78 ?? 01 0F 85 ???????? FF");
-            ptr = retry(|| SIGSTATUS.scan_process_range(process, (base, size))).await + 1;
-            status_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            status_offset = retry(|| process.read::<u8>(ptr + 6)).await as u32;
-
-            // Health addresses for the bosses in Egg Reverie
-            const SIG_ER: Signature<13> = Signature::new("8B ?? ???????? 8B ?? 40 83 ?? ?? 00");
-            ptr = retry(|| SIG_ER.scan_process_range(process, (base, size))).await + 2;
-            egg_reverie_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            egg_reverie1_offset1 = retry(|| process.read::<u8>(ptr + 6)).await as _;
-            egg_reverie1_offset2 = retry(|| process.read::<u8>(ptr + 9)).await as _;
-            egg_reverie2_offset1 = retry(|| process.read::<u8>(ptr + 15)).await as _;
-            egg_reverie2_offset2 = retry(|| process.read::<u8>(ptr + 18)).await as _;
-
-            // Flag that immediately tells when we defeated Eggman in Titanic Monarch Act 2
-            const SIG_EM1: Signature<8> = Signature::new("A1 ???????? 83 C0 78");
-            ptr = retry(|| SIG_EM1.scan_process_range(process, (base, size))).await + 1;
-            egg_monarch_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            const SIG_EM2: Signature<24> =
-                Signature::new("6A 00 C7 80 ???????? 01 00 00 00 A1 ???????? C7 ?? ?? 00 00 00 00");
-            ptr = retry(|| SIG_EM2.scan_process_range(process, (base, size))).await + 4;
-            egg_monarch_offset = retry(|| process.read::<u32>(ptr)).await;
-
-            // Bitmask for the Chaos Emeralds. One bit for every emerald. 0x7F = 7 emeralds.
-            const SIG_CE: Signature<14> = Signature::new("7D ?? A1 ???????? 8B ?? ?? 83 ?? ?? 7F");
-            ptr = retry(|| SIG_CE.scan_process_range(process, (base, size))).await + 3;
-            chaos_emerald_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            chaos_emerald_offset1 = retry(|| process.read::<u8>(ptr + 6)).await as _;
-            chaos_emerald_offset2 = retry(|| process.read::<u8>(ptr + 9)).await as _;
-
-            // Characters
-            const SIG_CHARACTER: Signature<10> = Signature::new("8B 15 ???????? 8B 4A 04 C1");
-            ptr = retry(|| SIG_CHARACTER.scan_process_range(process, (base, size))).await + 2;
-            character_base = retry(|| process.read::<Address32>(ptr)).await.into();
-            character_offset = retry(|| process.read::<u8>(ptr + 6)).await as _;
-
-            // The start trigger monitors whenever the game reports a successful selection of the save file
-            // Very janky solution, but it works.
-            const SIG_SAVE: Signature<14> = Signature::new("69 C0 ???????? 05 ???????? 5D C3 CC");
-            ptr = retry(|| SIG_SAVE.scan_process_range(process, main_module)).await + 7;
-            ptr = retry(|| process.read::<Address32>(ptr)).await.into();
-            start_trigger = ptr + 0x71808 + if is_rsdk_5u { 0xD14 } else { 0 };
-            mania_mode_selection = ptr + 0x720AC + if is_rsdk_5u { 0xD24 } else { 0 };
-            save_selection_mania = ptr + 0x720C0 + if is_rsdk_5u { 0xD24 } else { 0 };
-            save_0 = ptr + 0x146DC + if is_rsdk_5u { 0x25C } else { 0 };
-            save_1 = ptr + 0x146DC + if is_rsdk_5u { 0x25C + 0x460 } else { 0x458 };
-            save_2 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 2
-                } else {
-                    0x458 * 2
-                };
-            save_3 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 3
-                } else {
-                    0x458 * 3
-                };
-            save_4 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 4
-                } else {
-                    0x458 * 4
-                };
-            save_5 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 5
-                } else {
-                    0x458 * 5
-                };
-            save_6 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 6
-                } else {
-                    0x458 * 6
-                };
-            save_7 = ptr
-                + 0x146DC
-                + if is_rsdk_5u {
-                    0x25C + 0x460 * 7
-                } else {
-                    0x458 * 7
-                };
-
-            save_selection_encore = ptr + 0x7A768 + if is_rsdk_5u { 0xE1C } else { 0 };
-            encore_save_1 = ptr + 0x6F5F4 + if is_rsdk_5u { 0xCD4 + 0x460 } else { 0x458 };
-            encore_save_2 = ptr
-                + 0x6F5F4
-                + if is_rsdk_5u {
-                    0xCD4 + 0x460 * 2
-                } else {
-                    0x458 * 2
-                };
-            encore_save_3 = ptr
-                + 0x6F5F4
-                + if is_rsdk_5u {
-                    0xCD4 + 0x460 * 3
-                } else {
-                    0x458 * 3
-                };
-        } else {
-            // 64-bit version (only for decomps)
-            // Detect if we're on rsdk5U or not. It alters some of the offsets later.
-            const SIGRSDK5U: Signature<15> =
-                Signature::new("81 F9 ???????? 0F 87 ???????? 41 8B 8C");
-            is_rsdk_5u = SIGRSDK5U.scan_process_range(process, main_module).is_some();
-
-            // LevelID. Actually a SceneID, it also identifies whenever we are in the main menu
-            const SIG: Signature<14> = Signature::new("48 8B ?? ???????? 48 85 C9 74 2A 80 7A");
-            ptr = retry(|| SIG.scan_process_range(process, (base, size))).await + 3;
-            level_id_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            level_id_offset = retry(|| process.read::<u8>(ptr + 30)).await as _;
-
-            // In-game timer. Doesn't even need an explanation, though we are not gonna use it directly
-            const SIGSTATUS: Signature<18> =
-                Signature::new("48 8B ?? ???????? 80 78 ?? 01 0F 85 ???????? FF");
-            ptr = retry(|| SIGSTATUS.scan_process_range(process, (base, size))).await + 3;
-            status_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            status_offset = retry(|| process.read::<u8>(ptr + 6)).await as _;
-
-            // Health addresses for the bosses in Egg Reverie
-            const SIG_ER: Signature<14> = Signature::new("75 ?? 48 8B 0D ???????? 48 8B 41 ?? 83");
-            ptr = retry(|| SIG_ER.scan_process_range(process, (base, size))).await + 5;
-            egg_reverie_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            egg_reverie1_offset1 = retry(|| process.read::<u8>(ptr + 7)).await as _;
-            egg_reverie1_offset2 = retry(|| process.read::<u8>(ptr + 10)).await as _;
-            const SIG_ER_2: Signature<14> =
-                Signature::new("75 ?? 48 8B 0D ???????? 48 8B 41 ?? 83");
-            ptr = retry(|| SIG_ER_2.scan_process_range(process, (base, size))).await;
-            egg_reverie2_offset1 = retry(|| process.read::<u8>(ptr + 5)).await as _;
-            egg_reverie2_offset2 = retry(|| process.read::<u8>(ptr + 8)).await as _;
-
-            // Flag that immediately tells when we defeated Eggman in Titanic Monarch Act 2
-            const SIG_EM: Signature<20> =
-                Signature::new("89 73 ?? 48 8B ?? ???????? C7 ?? ???????? 01 00 00 00");
-            ptr = retry(|| SIG_EM.scan_process_range(process, (base, size))).await + 6;
-            egg_monarch_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            egg_monarch_offset = retry(|| process.read::<u32>(ptr + 6)).await as _;
-
-            // Bitmask for the Chaos Emeralds. One bit for every emerald. 0x7F = 7 emeralds.
-            const SIG_CE: Signature<18> =
-                Signature::new("7D ?? 48 8B ?? ???????? 48 ?? ?? ?? 83 ?? ?? 7F 7C");
-            ptr = retry(|| SIG_CE.scan_process_range(process, (base, size))).await + 5;
-            chaos_emerald_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            chaos_emerald_offset1 = retry(|| process.read::<u8>(ptr + 7)).await as _;
-            chaos_emerald_offset2 = retry(|| process.read::<u8>(ptr + 10)).await as _;
-
-            // Characters
-            const SIG_CHARACTER: Signature<13> =
-                Signature::new("48 8B ?? ???????? 8B 4A ?? C1 F9 08");
-            ptr = retry(|| SIG_CHARACTER.scan_process_range(process, (base, size))).await + 3;
-            character_base = ptr + 0x4 + retry(|| process.read::<i32>(ptr)).await;
-            character_offset = retry(|| process.read::<u8>(ptr + 6)).await as _;
-
-            // The start trigger monitors whenever the game reports a successful selection of the save file
-            // Very janky solution, but it works.
-            const SIG_SAVE: Signature<18> =
-                Signature::new("4C 8D ?? ???????? 8B D0 4C 8D ?? ???????? 0F 1F");
-            ptr = retry(|| SIG_SAVE.scan_process_range(process, main_module)).await + 3;
-            ptr = ptr + retry(|| process.read::<i32>(ptr)).await + 0x4;
-            start_trigger = ptr + 0xD977A + if is_rsdk_5u { 0x1A10 } else { 0 };
-            mania_mode_selection = ptr + 0xDA81E + if is_rsdk_5u { 0x1A30 } else { 0 };
-            save_selection_mania = ptr + 0xDA836 + if is_rsdk_5u { 0x1A30 } else { 0 };
-            save_0 = ptr + 0x26A9A + if is_rsdk_5u { 0x4A0 } else { 0 };
-            save_1 = ptr + 0x26A9A + if is_rsdk_5u { 0x4A0 + 0x868 } else { 0x858 };
-            save_2 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 2
-                } else {
-                    0x858 * 2
-                };
-            save_3 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 3
-                } else {
-                    0x858 * 3
-                };
-            save_4 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 4
-                } else {
-                    0x858 * 4
-                };
-            save_5 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 5
-                } else {
-                    0x858 * 5
-                };
-            save_6 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 6
-                } else {
-                    0x858 * 6
-                };
-            save_7 = ptr
-                + 0x26A9A
-                + if is_rsdk_5u {
-                    0x4A0 + 0x868 * 7
-                } else {
-                    0x858 * 7
-                };
+        // Upon entering a new act, the time of the previous one is final
+        if level_id.changed() {
+            self.accumulated += self.current_act;
+            self.current_act = Duration::ZERO;
+        }
 
-            save_selection_encore = ptr + 0xEAADE + if is_rsdk_5u { 0x1C20 } else { 0 };
-            encore_save_1 = ptr + 0xD55AA + if is_rsdk_5u { 0x1990 + 0x868 } else { 0x858 };
-            encore_save_2 = ptr
-                + 0xD55AA
-                + if is_rsdk_5u {
-                    0x1990 + 0x868 * 2
-                } else {
-                    0x858 * 2
-                };
-            encore_save_3 = ptr
-                + 0xD55AA
-                + if is_rsdk_5u {
-                    0x1990 + 0x868 * 3
-                } else {
-                    0x858 * 3
-                };
+        // The timer in Egg Reverie is scrambled, so it can't be used
+        if level_id.current == Acts::EggReverie {
+            return;
         }
 
-        Self {
-            is_64_bit,
-            level_id_base,
-            level_id_offset,
-            status_base,
-            status_offset,
-            egg_reverie_base,
-            egg_reverie1_offset1,
-            egg_reverie1_offset2,
-            egg_reverie2_offset1,
-            egg_reverie2_offset2,
-            egg_monarch_base,
-            egg_monarch_offset,
-            chaos_emerald_base,
-            chaos_emerald_offset1,
-            chaos_emerald_offset2,
-            character_base,
-            character_offset,
-            start_trigger,
-            mania_mode_selection,
-            save_selection_mania,
-            save_0,
-            save_1,
-            save_2,
-            save_3,
-            save_4,
-            save_5,
-            save_6,
-            save_7,
-            save_selection_encore,
-            encore_save_1,
-            encore_save_2,
-            encore_save_3,
+        // The act timer goes back to zero while loading a stage, so we need to ignore it
+        // in order to keep the final time of the act we just completed. Deaths and restarts
+        // rewind the timer, and the time lost that way is not counted by the game either.
+        if igt.current != Duration::ZERO {
+            self.current_act = igt.current;
         }
     }
-}
 
-fn update_loop(game: &Process, addresses: &Addresses, watchers: &mut Watchers) {
-    let game_mode: u8;
-    let levelid: u8;
-    let status: [u8; 5];
-    let mut enum_levelid: Acts;
-    let egg_reverie_monarch_health: u8;
-    let egg_reverie_eggman_health: u8;
-    let tm2_defeat: u8;
-    let chaos_emeralds: u8;
-    let characters: [u8; 2];
-
-    if addresses.is_64_bit {
-        game_mode = game
-            .read_pointer_path64(addresses.character_base, &[0, 0])
-            .ok()
-            .unwrap_or_default();
-        levelid = game
-            .read_pointer_path64(
-                addresses.level_id_base,
-                &[0, addresses.level_id_offset as u64],
-            )
-            .ok()
-            .unwrap_or_default();
-        status = game
-            .read_pointer_path64(addresses.status_base, &[0, addresses.status_offset as u64])
-            .ok()
-            .unwrap_or_default();
-        egg_reverie_monarch_health = game
-            .read_pointer_path64(
-                addresses.egg_reverie_base,
-                &[
-                    0,
-                    addresses.egg_reverie1_offset1 as u64,
-                    addresses.egg_reverie1_offset2 as u64,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        egg_reverie_eggman_health = game
-            .read_pointer_path64(
-                addresses.egg_reverie_base,
-                &[
-                    0,
-                    addresses.egg_reverie2_offset1 as u64,
-                    addresses.egg_reverie2_offset2 as u64,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        tm2_defeat = game
-            .read_pointer_path64(
-                addresses.egg_monarch_base,
-                &[0, addresses.egg_monarch_offset as u64],
-            )
-            .ok()
-            .unwrap_or_default();
-        chaos_emeralds = game
-            .read_pointer_path64(
-                addresses.chaos_emerald_base,
-                &[
-                    0,
-                    addresses.chaos_emerald_offset1 as u64,
-                    addresses.chaos_emerald_offset2 as u64,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        characters = game
-            .read_pointer_path64(
-                addresses.character_base,
-                &[0, addresses.character_offset as u64],
-            )
-            .ok()
-            .unwrap_or_default();
-    } else {
-        game_mode = game
-            .read_pointer_path32(addresses.character_base, &[0, 0])
-            .ok()
-            .unwrap_or_default();
-        levelid = game
-            .read_pointer_path32(addresses.level_id_base, &[0, addresses.level_id_offset])
-            .ok()
-            .unwrap_or_default();
-        status = game
-            .read_pointer_path32(addresses.status_base, &[0, addresses.status_offset])
-            .ok()
-            .unwrap_or_default();
-        egg_reverie_monarch_health = game
-            .read_pointer_path32(
-                addresses.egg_reverie_base,
-                &[
-                    0,
-                    addresses.egg_reverie1_offset1,
-                    addresses.egg_reverie1_offset2,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        egg_reverie_eggman_health = game
-            .read_pointer_path32(
-                addresses.egg_reverie_base,
-                &[
-                    0,
-                    addresses.egg_reverie2_offset1,
-                    addresses.egg_reverie2_offset2,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        tm2_defeat = game
-            .read_pointer_path32(
-                addresses.egg_monarch_base,
-                &[0, addresses.egg_monarch_offset],
-            )
-            .ok()
-            .unwrap_or_default();
-        chaos_emeralds = game
-            .read_pointer_path32(
-                addresses.chaos_emerald_base,
-                &[
-                    0,
-                    addresses.chaos_emerald_offset1,
-                    addresses.chaos_emerald_offset2,
-                ],
-            )
-            .ok()
-            .unwrap_or_default();
-        characters = game
-            .read_pointer_path32(addresses.character_base, &[0, addresses.character_offset])
-            .ok()
-            .unwrap_or_default();
+    fn total(&self) -> Duration {
+        self.accumulated + self.current_act
     }
 
-    // Level ID logic
-    enum_levelid = match &watchers.level_id.pair {
-        Some(lvl) => lvl.current,
-        _ => Acts::GreenHill1,
-    };
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
 
-    // If level ID == 37, it's always Egg Reverie. Don't even consider the IGT because it's scrambled in that stage
-    if levelid == 37 {
-        enum_levelid = Acts::EggReverie;
-    } else if status[0] == 2 || (status[0] != 0 && status[2] + status[3] + status[4] == 0) {
-        enum_levelid = match game_mode {
-            0 => match levelid {
-                9 | 117 | 119 => Acts::GreenHill1,
-                10 | 120 => Acts::GreenHill2,
-                11 => Acts::ChemicalPlant1,
-                12 => Acts::ChemicalPlant2,
-                13 => Acts::Studiopolis1,
-                14 => Acts::Studiopolis2,
-                15 => Acts::FlyingBattery1,
-                16 => Acts::FlyingBattery2,
-                17 => Acts::PressGarden1,
-                18 => Acts::PressGarden2,
-                19 | 122 => Acts::StardustSpeedway1,
-                20 | 21 => Acts::StardustSpeedway2,
-                22 => Acts::Hydrocity1,
-                23 => Acts::Hydrocity2,
-                24 | 25 => Acts::MirageSaloon1,
-                26 => Acts::MirageSaloon2,
-                27 => Acts::OilOcean1,
-                28 => Acts::OilOcean2,
-                29 => Acts::LavaReef1,
-                30 | 31 => Acts::LavaReef2,
-                32 => Acts::MetallicMadness1,
-                33 => Acts::MetallicMadness2,
-                34 => Acts::TitanicMonarch1,
-                35 | 36 => Acts::TitanicMonarch2,
-                37 => Acts::EggReverie,
-                _ => enum_levelid,
-            },
-            _ => match levelid {
-                118 => Acts::EncoreAngelIsland,
-                119 | 38 => Acts::EncoreGreenHill1,
-                39 | 120 => Acts::EncoreGreenHill2,
-                40 => Acts::EncoreChemicalPlant1,
-                41 => Acts::EncoreChemicalPlant2,
-                42 => Acts::EncoreStudiopolis1,
-                43 => Acts::EncoreStudiopolis2,
-                44 => Acts::EncoreFlyingBattery1,
-                45 => Acts::EncoreFlyingBattery2,
-                46 => Acts::EncorePressGarden1,
-                47 => Acts::EncorePressGarden2,
-                48 | 122 => Acts::EncoreStardustSpeedway1,
-                49 | 50 => Acts::EncoreStardustSpeedway2,
-                51 => Acts::EncoreHydrocity1,
-                52 => Acts::EncoreHydrocity2,
-                53 => Acts::EncoreMirageSaloon1,
-                54 => Acts::EncoreMirageSaloon2,
-                55 => Acts::EncoreOilOcean1,
-                56 => Acts::EncoreOilOcean2,
-                57 => Acts::EncoreLavaReef1,
-                58 | 59 => Acts::EncoreLavaReef2,
-                60 => Acts::EncoreMetallicMadness1,
-                61 => Acts::EncoreMetallicMadness2,
-                62 => Acts::EncoreTitanicMonarch1,
-                63 | 64 => Acts::EncoreTitanicMonarch2,
-                _ => enum_levelid,
-            },
+/// Load detection, used when the run is timed with load removed time.
+/// The game gets considered as loading during scene transitions, title cards,
+/// results screens and menus, until the player gets control back.
+#[derive(Default)]
+struct LoadRemoval {
+    state: LoadState,
+}
+
+impl LoadRemoval {
+    fn update(&mut self, watchers: &Watchers) {
+        let Some(status) = &watchers.status.pair else {
+            return;
+        };
+        let Some(levelid) = &watchers.level_id_numeric.pair else {
+            return;
+        };
+        let Some(igt) = &watchers.igt.pair else {
+            return;
+        };
+        let Some(in_act) = &watchers.in_act.pair else {
+            return;
+        };
+        let Some(act_clear) = &watchers.act_clear.pair else {
+            return;
+        };
+
+        let timer_running = igt.current > igt.old;
+
+        self.state = if levelid.current == 1 || levelid.current == 2 {
+            LoadState::Menu
+        } else if status.current == 0 || levelid.changed() {
+            LoadState::Loading
+        } else if status.current == 2 {
+            LoadState::TitleCard
+        } else if status.current != 1 {
+            // Dev menu, pause, etc. Nothing changes here
+            self.state
+        } else {
+            match self.state {
+                // When coming back from a load, control is given back to the player once the act timer starts running.
+                // Outside of the acts (eg. special stages) there is no timer to look at, so we resume immediately.
+                LoadState::Menu | LoadState::Loading | LoadState::TitleCard => {
+                    if timer_running || !in_act.current {
+                        LoadState::Playing
+                    } else {
+                        LoadState::Loading
+                    }
+                }
+                // The act timer stops on the results screen and goes back to zero once the next act starts loading
+                LoadState::ActClear => {
+                    if igt.current == Duration::ZERO {
+                        LoadState::Loading
+                    } else if timer_running {
+                        LoadState::Playing
+                    } else {
+                        LoadState::ActClear
+                    }
+                }
+                LoadState::Playing => {
+                    if act_clear.current {
+                        LoadState::ActClear
+                    } else {
+                        LoadState::Playing
+                    }
+                }
+            }
         };
     }
-    watchers.level_id.update(Some(enum_levelid));
 
-    watchers.game_mode.update(Some(if game_mode == 0 {
-        GameMode::Standard
-    } else if game_mode == 1 {
-        GameMode::Encore
-    } else {
-        GameMode::Invalid
-    }));
-    watchers.level_id_numeric.update(Some(levelid));
-    watchers.status.update(Some(status[0]));
-    watchers
-        .egg_reverie_health
-        .update(Some(egg_reverie_eggman_health + egg_reverie_monarch_health));
-    watchers
-        .titanic_monarch_2_defeated
-        .update(Some(tm2_defeat != 0));
-    watchers.chaos_emeralds.update(Some(chaos_emeralds));
-    watchers.character_1.update(Some(characters[0]));
-    watchers.character_2.update(Some(characters[1]));
-
-    watchers.start_trigger.update(Some(
-        game.read(addresses.start_trigger).ok().unwrap_or_default(),
-    ));
-    watchers.mania_mode_selection.update(Some(
-        game.read::<u8>(addresses.mania_mode_selection)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    ));
-    watchers.save_selection_mania.update(Some(
-        game.read(addresses.save_selection_mania)
-            .ok()
-            .unwrap_or_default(),
-    ));
-    watchers
-        .save_0
-        .update(Some(game.read(addresses.save_0).ok().unwrap_or_default()));
-    watchers
-        .save_1
-        .update(Some(game.read(addresses.save_1).ok().unwrap_or_default()));
-    watchers
-        .save_2
-        .update(Some(game.read(addresses.save_2).ok().unwrap_or_default()));
-    watchers
-        .save_3
-        .update(Some(game.read(addresses.save_3).ok().unwrap_or_default()));
-    watchers
-        .save_4
-        .update(Some(game.read(addresses.save_4).ok().unwrap_or_default()));
-    watchers
-        .save_5
-        .update(Some(game.read(addresses.save_5).ok().unwrap_or_default()));
-    watchers
-        .save_6
-        .update(Some(game.read(addresses.save_6).ok().unwrap_or_default()));
-    watchers
-        .save_7
-        .update(Some(game.read(addresses.save_7).ok().unwrap_or_default()));
-    watchers.save_selection_encore.update(Some(
-        game.read(addresses.save_selection_encore)
-            .ok()
-            .unwrap_or_default(),
-    ));
-    watchers.save_encore_1.update(Some(
-        game.read::<u8>(addresses.encore_save_1)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    ));
-    watchers.save_encore_2.update(Some(
-        game.read::<u8>(addresses.encore_save_2)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    ));
-    watchers.save_encore_3.update(Some(
-        game.read::<u8>(addresses.encore_save_3)
-            .ok()
-            .unwrap_or_default()
-            != 0,
-    ));
+    fn is_loading(&self) -> bool {
+        self.state != LoadState::Playing
+    }
 }
 
-fn start(watchers: &Watchers, settings: &Settings) -> bool {
-    if !settings.start_mania_mode && !settings.start_encore_mode {
-        return false;
+/// Keeps a record of the characters being played, which can change during the run in Encore mode.
+/// The leader at the moment each act gets cleared is stored, so the run can be verified against
+/// the category rules, and everything is exposed to LiveSplit as timer variables.
+#[derive(Default)]
+struct CharacterLog {
+    /// Whether the characters have been published since attaching to the game
+    published: bool,
+    /// Acts cleared in the run, each with the leader that cleared it
+    finished_acts: ArrayString<2048>,
+}
+
+impl CharacterLog {
+    fn update(&mut self, watchers: &Watchers) {
+        let Some(leader) = &watchers.character_1.pair else {
+            return;
+        };
+        let Some(buddy) = &watchers.character_2.pair else {
+            return;
+        };
+        let Some(stock) = &watchers.stock.pair else {
+            return;
+        };
+        let Some(act_clear) = &watchers.act_clear.pair else {
+            return;
+        };
+        let Some(level_id) = &watchers.level_id.pair else {
+            return;
+        };
+
+        // The first values read never count as a change, but still need to be shown
+        let first_tick = !self.published;
+        self.published = true;
+
+        if first_tick || leader.changed() {
+            runtime::set_variable("Leader", leader.current.name());
+        }
+        if first_tick || buddy.changed() {
+            runtime::set_variable("Buddy", buddy.current.name());
+        }
+        if first_tick || stock.changed() {
+            let mut names = ArrayString::<32>::new();
+            for character in stock.current.iter().filter(|&&c| c != Character::None) {
+                if !names.is_empty() {
+                    names.push_str(", ");
+                }
+                names.push_str(character.name());
+            }
+            runtime::set_variable("Stock", &names);
+        }
+
+        if act_clear.current && !act_clear.old {
+            if !self.finished_acts.is_empty() {
+                let _ = self.finished_acts.try_push_str(", ");
+            }
+            let mut act = ArrayString::<64>::new();
+            let _ = write!(
+                act,
+                "{}: {}",
+                level_id.current.name(),
+                leader.current.name()
+            );
+            let _ = self.finished_acts.try_push_str(&act);
+            runtime::set_variable("Act cleared by", leader.current.name());
+            runtime::set_variable("Acts cleared by", &self.finished_acts);
+        }
     }
 
-    // If you're not in the main menu, there's no reason to continue
-    let Some(levelid) = &watchers.level_id_numeric.pair else {
-        return false;
-    };
-    if levelid.current != 2 {
-        return false;
-    };
+    fn reset(&mut self) {
+        self.finished_acts.clear();
+        runtime::set_variable("Act cleared by", "");
+        runtime::set_variable("Acts cleared by", "");
+    }
+}
 
-    // If the main trigger doesn't get involved, there's no reason to continue
-    let Some(start_trigger) = &watchers.start_trigger.pair else {
-        return false;
-    };
-    if start_trigger.current != start_trigger.old + 48 {
-        return false;
-    };
+/// Counts the deaths and the restarts from the pause menu, both in the current act and in the whole run.
+///
+/// Both of them reload the act, bringing the act timer back to the last star post (or zero), so we
+/// look for the timer going backwards in the same act. Restarting reloads the act straight from the
+/// pause menu, while dying reloads it with the game still running after the death animation. The
+/// lives would tell them apart as well, but they can't be read from memory yet.
+#[derive(Default)]
+struct DeathCounter {
+    /// Whether the game was running, rather than paused, when the last load started
+    loaded_while_running: bool,
+    /// Level ID, act timer and whether the game was running before the act got reloaded
+    reload: Option<(u8, Duration, bool)>,
+    /// Whether the player has been in control in the current act
+    in_control: bool,
+    deaths: u32,
+    restarts: u32,
+    deaths_in_act: u32,
+    /// Deaths in every act of the run, in the order they have been played
+    deaths_per_act: ArrayString<2048>,
+}
 
-    let Some(mania_mode_selection) = &watchers.mania_mode_selection.pair else {
-        return false;
-    };
-    if mania_mode_selection.current {
-        if !settings.start_mania_mode {
-            return false;
+impl DeathCounter {
+    fn update(&mut self, watchers: &Watchers) {
+        let Some(game_mode) = &watchers.game_mode.pair else {
+            return;
         };
-
-        // If, for whatever reason, the save selection is out of bounds, do not continue
-        let Some(save_selection_mania) = &watchers.save_selection_mania.pair else {
-            return false;
+        let Some(level_id) = &watchers.level_id.pair else {
+            return;
         };
-        if save_selection_mania.current > 8 {
-            return false;
+        let Some(level_id_numeric) = &watchers.level_id_numeric.pair else {
+            return;
+        };
+        let Some(status) = &watchers.status.pair else {
+            return;
+        };
+        let Some(in_act) = &watchers.in_act.pair else {
+            return;
+        };
+        let Some(igt) = &watchers.igt.pair else {
+            return;
+        };
+
+        // Restarting is part of the game in Time Attack
+        if game_mode.current == GameMode::TimeAttack {
+            self.reload = None;
+            return;
+        }
+
+        if level_id.changed() && self.in_control {
+            self.finish_act(level_id.old);
+        }
+        if status.current == 1 && in_act.current {
+            self.in_control = true;
+        }
+
+        if status.current == 0 && status.old != 0 {
+            self.loaded_while_running = status.old == 1;
+        }
+        if igt.current < igt.old && self.reload.is_none() {
+            let running = match status.current {
+                0 => self.loaded_while_running,
+                _ => status.old == 1,
+            };
+            self.reload = Some((level_id_numeric.old, igt.old, running));
+        }
+
+        // Wait for the player to be back in control before deciding what happened. Going to
+        // another act or coming back from a Special Stage doesn't count.
+        if let Some((level, time, running)) = self.reload {
+            if status.current == 1 && in_act.current {
+                self.reload = None;
+                if level_id_numeric.current == level && igt.current < time {
+                    if running {
+                        self.deaths += 1;
+                        self.deaths_in_act += 1;
+                    } else {
+                        self.restarts += 1;
+                    }
+                    self.publish();
+                }
+            }
+        }
+    }
+
+    fn finish_act(&mut self, act: Acts) {
+        if !self.deaths_per_act.is_empty() {
+            let _ = self.deaths_per_act.try_push_str(", ");
+        }
+        let mut count = ArrayString::<64>::new();
+        let _ = write!(count, "{}: {}", act.name(), self.deaths_in_act);
+        let _ = self.deaths_per_act.try_push_str(&count);
+        self.deaths_in_act = 0;
+        self.in_control = false;
+        self.publish();
+    }
+
+    fn publish(&self) {
+        let mut value = ArrayString::<10>::new();
+        let _ = write!(value, "{}", self.deaths);
+        runtime::set_variable("Deaths", &value);
+        value.clear();
+        let _ = write!(value, "{}", self.deaths_in_act);
+        runtime::set_variable("Deaths in act", &value);
+        value.clear();
+        let _ = write!(value, "{}", self.restarts);
+        runtime::set_variable("Restarts", &value);
+        runtime::set_variable("Deaths per act", &self.deaths_per_act);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+        self.publish();
+    }
+}
+
+/// Compares the number of splits enabled in the settings with the segments of the splits file.
+///
+/// The runtime doesn't tell how many segments there are. When the runner entered it in the settings,
+/// it's checked as soon as the timer starts. Otherwise the check happens while running: the timer
+/// ending before all the enabled splits, or not ending after all of them, means they don't match.
+/// Splits depending on how the run goes (eg. star posts) only allow checking the first case.
+#[derive(Default)]
+struct SplitCheck {
+    expected: u8,
+    exact: bool,
+    splits: u8,
+    warned: bool,
+}
+
+impl SplitCheck {
+    fn start(&mut self, watchers: &Watchers, settings: &Settings) {
+        let time_attack = matches!(
+            &watchers.game_mode.pair,
+            Some(mode) if mode.current == GameMode::TimeAttack
+        );
+        let encore = matches!(
+            &watchers.mania_mode_selection.pair,
+            Some(selection) if !selection.current
+        );
+
+        *self = if time_attack || settings.practice_mode {
+            Self {
+                expected: 1,
+                exact: true,
+                ..Default::default()
+            }
+        } else {
+            Self::count(settings, encore)
+        };
+
+        let mut text = ArrayString::<32>::new();
+        if !self.exact {
+            text.push_str("at least ");
+        }
+        let _ = write!(text, "{}", self.expected);
+        runtime::set_variable("Expected splits", &text);
+        runtime::set_variable("Splits check", "OK");
+        self.check_segments(settings.segments);
+    }
+
+    /// Compares the segments entered in the settings with the splits expected
+    fn check_segments(&mut self, segments: Segments) {
+        let Some(segments) = segments.count() else {
+            return;
+        };
+
+        if segments < self.expected {
+            self.warn("The splits file has fewer segments than the splits enabled in the settings");
+        } else if self.exact && segments > self.expected {
+            self.warn("The splits file has more segments than the splits enabled in the settings");
+        }
+    }
+
+    fn count(settings: &Settings, encore: bool) -> Self {
+        let acts = settings.route.acts(encore);
+        let titanic_monarch_2 = match encore {
+            true => Acts::EncoreTitanicMonarch2,
+            false => Acts::TitanicMonarch2,
+        };
+
+        // When splitting on act clear, an Act 2 boss split replaces the split of its act
+        let replaced_by_boss = |act: Acts| {
+            settings.split_on_act_clear
+                && act != titanic_monarch_2
+                && Boss::from_act(act).is_some_and(|boss| settings.boss_split_enabled(boss))
+        };
+        let expected = acts
+            .iter()
+            .filter(|&&act| settings.split_enabled(act) && !replaced_by_boss(act))
+            .count()
+            + acts
+                .iter()
+                .filter_map(|&act| Boss::from_act(act))
+                .filter(|&boss| settings.boss_split_enabled(boss))
+                .count();
+
+        let variable = settings.route == Route::AnyOrder
+            || (0..7).any(|bit| settings.chaos_emerald_split_enabled(bit))
+            || settings.special_stage_failed
+            || settings.medal_earned
+            || settings.bonus_stage_failed
+            || settings.bonus_stage_exited
+            || acts.iter().any(|&act| settings.star_posts(act) != StarPosts::None)
+            // On the standard route, Egg Reverie is only reached with all the Chaos Emeralds
+            || (!encore && settings.route == Route::Standard && settings.split_enabled(Acts::EggReverie))
+            // The boss and the end of the act can be detected on the same frame, resulting in a single split
+            || (settings.boss_titanic_monarch && settings.split_enabled(titanic_monarch_2));
+
+        Self {
+            expected: expected as u8,
+            exact: !variable,
+            ..Default::default()
+        }
+    }
+
+    fn split(&mut self) {
+        self.splits = self.splits.saturating_add(1);
+    }
+
+    fn undo(&mut self) {
+        self.splits = self.splits.saturating_sub(1);
+    }
+
+    fn update(&mut self, timer_state: TimerState) {
+        if self.warned || self.expected == 0 {
+            return;
+        }
+
+        if timer_state == TimerState::Ended && self.splits < self.expected {
+            self.warn("The splits file has fewer segments than the splits enabled in the settings");
+        } else if timer_state == TimerState::Running && self.exact && self.splits >= self.expected {
+            self.warn("The splits file has more segments than the splits enabled in the settings");
+        }
+    }
+
+    fn warn(&mut self, message: &str) {
+        self.warned = true;
+        runtime::print_message(message);
+        runtime::set_variable("Splits check", message);
+    }
+}
+
+/// Updates the timer variables describing the progress made in the game
+fn update_stats(watchers: &Watchers, first_update: bool) {
+    let Some(level_id) = &watchers.level_id.pair else {
+        return;
+    };
+    let Some(in_act) = &watchers.in_act.pair else {
+        return;
+    };
+    let Some(medals) = &watchers.medals.pair else {
+        return;
+    };
+
+    // The act is kept while in the menus and stages outside of the acts, so it only needs
+    // to be published again when entering a new one
+    if first_update || level_id.changed() || (in_act.changed() && in_act.current) {
+        runtime::set_variable("Current act", &level_id.current.name());
+    }
+
+    if let Some(boss) = &watchers.boss.pair {
+        if let (BossState::Fighting(_), BossState::Defeated(boss)) = (boss.old, boss.current) {
+            runtime::set_variable("Last boss defeated", boss.name());
+        }
+    }
+
+    if first_update || medals.changed() {
+        let mut text = ArrayString::<32>::new();
+        let _ = write!(
+            text,
+            "{} ({} gold, {} silver)",
+            medals.current.gold + medals.current.silver,
+            medals.current.gold,
+            medals.current.silver
+        );
+        runtime::set_variable("Medals", &text);
+    }
+}
+
+/// Declares the settings. The replay tests can't register them with the runtime, so the defaults they
+/// use are collected from the same attributes as the GUI ones.
+macro_rules! settings {
+    (
+        $(#[$($attr:tt)*])*
+        struct $name:ident {
+            $($(#[$($field_attr:tt)*])* $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$($attr)*])*
+        struct $name {
+            $($(#[$($field_attr)*])* $field: $ty,)*
+        }
+
+        #[cfg(test)]
+        impl $name {
+            /// Keys the settings are stored with
+            const KEYS: &'static [&'static str] = &[$(stringify!($field)),*];
+        }
+
+        #[cfg(test)]
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: tests::registered_default(&[$(stringify!($($field_attr)*)),*]),)*
+                }
+            }
+        }
+    };
+}
+
+settings! {
+    #[derive(Gui)]
+    struct Settings {
+        /// Category preset, setting the start, reset and split settings below when picked
+        preset: Preset,
+        /// Auto start
+        #[heading_level = 0]
+        _starts: Title,
+        #[default = true]
+        /// Enable auto start in Mania mode
+        start_mania_mode: bool,
+        #[default = true]
+        /// Enable auto start in Encore mode
+        start_encore_mode: bool,
+        #[default = false]
+        /// Also start on saves with some progress already (New Game+, zone select), if they match the starting point below
+        start_from_save: bool,
+        /// Zone the save has to be on
+        start_save_zone: Zone,
+        /// Character the save has to be played with (the leader in Encore mode)
+        start_save_character: SaveCharacter,
+        /// Auto reset
+        #[heading_level = 0]
+        _resets: Title,
+        #[default = true]
+        /// Auto reset when returning to the save selection screen
+        reset_save_select: bool,
+        #[default = false]
+        /// Auto reset when opening the dev menu
+        reset_dev_menu: bool,
+        /// General
+        #[heading_level = 0]
+        _general: Title,
+        #[default = true]
+        /// Enable auto start, split and reset in Time Attack mode
+        time_attack: bool,
+        /// Timing method used for the game time
+        timing_method: TimingMethod,
+        /// Route followed by the run, deciding which act transitions trigger a split
+        route: Route,
+        #[default = false]
+        /// Undo the last split when the game goes back to the previous act right after it
+        undo_false_splits: bool,
+        #[default = false]
+        /// Split as soon as an act is cleared (results screen), instead of when the next act loads
+        split_on_act_clear: bool,
+        /// Segments in the splits file, checked against the splits enabled as soon as the timer starts
+        segments: Segments,
+        /// Individual act practice
+        #[heading_level = 0]
+        _practice: Title,
+        #[default = false]
+        /// Start when the act below begins, reset when restarting it and split when it's cleared (replaces the other starts and splits)
+        practice_mode: bool,
+        /// Act to practise (Egg Reverie is not supported, as it doesn't use the act timer)
+        practice_act: Acts,
+        /// Mania mode
+        #[heading_level = 0]
+        _mania: Title,
+        #[default = true]
+        /// Split on the acts of Mania mode (governs every zone below)
+        mania_mode: bool,
+        /// Green Hill
+        #[heading_level = 1]
+        _mania_green_hill: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_green_hill: bool,
+        #[default = true]
+        /// Act 1
+        green_hill_1: bool,
+        #[default = true]
+        /// Act 2
+        green_hill_2: bool,
+        /// Chemical Plant
+        #[heading_level = 1]
+        _mania_chemical_plant: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_chemical_plant: bool,
+        #[default = true]
+        /// Act 1
+        chemical_plant_1: bool,
+        #[default = true]
+        /// Act 2
+        chemical_plant_2: bool,
+        /// Studiopolis
+        #[heading_level = 1]
+        _mania_studiopolis: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_studiopolis: bool,
+        #[default = true]
+        /// Act 1
+        studiopolis_1: bool,
+        #[default = true]
+        /// Act 2
+        studiopolis_2: bool,
+        /// Flying Battery
+        #[heading_level = 1]
+        _mania_flying_battery: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_flying_battery: bool,
+        #[default = true]
+        /// Act 1
+        flying_battery_1: bool,
+        #[default = true]
+        /// Act 2
+        flying_battery_2: bool,
+        /// Press Garden
+        #[heading_level = 1]
+        _mania_press_garden: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_press_garden: bool,
+        #[default = true]
+        /// Act 1
+        press_garden_1: bool,
+        #[default = true]
+        /// Act 2
+        press_garden_2: bool,
+        /// Stardust Speedway
+        #[heading_level = 1]
+        _mania_stardust_speedway: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_stardust_speedway: bool,
+        #[default = true]
+        /// Act 1
+        stardust_speedway_1: bool,
+        #[default = true]
+        /// Act 2
+        stardust_speedway_2: bool,
+        /// Hydrocity
+        #[heading_level = 1]
+        _mania_hydrocity: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_hydrocity: bool,
+        #[default = true]
+        /// Act 1
+        hydrocity_1: bool,
+        #[default = true]
+        /// Act 2
+        hydrocity_2: bool,
+        /// Mirage Saloon
+        #[heading_level = 1]
+        _mania_mirage_saloon: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_mirage_saloon: bool,
+        #[default = true]
+        /// Act 1
+        mirage_saloon_1: bool,
+        #[default = true]
+        /// Act 2
+        mirage_saloon_2: bool,
+        /// Oil Ocean
+        #[heading_level = 1]
+        _mania_oil_ocean: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_oil_ocean: bool,
+        #[default = true]
+        /// Act 1
+        oil_ocean_1: bool,
+        #[default = true]
+        /// Act 2
+        oil_ocean_2: bool,
+        /// Lava Reef
+        #[heading_level = 1]
+        _mania_lava_reef: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_lava_reef: bool,
+        #[default = true]
+        /// Act 1
+        lava_reef_1: bool,
+        #[default = true]
+        /// Act 2
+        lava_reef_2: bool,
+        /// Metallic Madness
+        #[heading_level = 1]
+        _mania_metallic_madness: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_metallic_madness: bool,
+        #[default = true]
+        /// Act 1
+        metallic_madness_1: bool,
+        #[default = true]
+        /// Act 2
+        metallic_madness_2: bool,
+        /// Titanic Monarch
+        #[heading_level = 1]
+        _mania_titanic_monarch: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        mania_titanic_monarch: bool,
+        #[default = true]
+        /// Act 1
+        titanic_monarch_1: bool,
+        #[default = true]
+        /// Act 2
+        titanic_monarch_2: bool,
+        /// Egg Reverie
+        #[heading_level = 1]
+        _mania_egg_reverie: Title,
+        #[default = true]
+        /// Egg Reverie (only reached with all the Chaos Emeralds)
+        egg_reverie: bool,
+        /// Encore mode
+        #[heading_level = 0]
+        _encore: Title,
+        #[default = true]
+        /// Split on the acts of Encore mode (governs every zone below)
+        encore_mode: bool,
+        /// Angel Island
+        #[heading_level = 1]
+        _encore_angel_island: Title,
+        #[default = true]
+        /// Angel Island
+        angel_island: bool,
+        /// Green Hill
+        #[heading_level = 1]
+        _encore_green_hill: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_green_hill: bool,
+        #[default = true]
+        /// Act 1
+        encore_green_hill_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_green_hill_2: bool,
+        /// Chemical Plant
+        #[heading_level = 1]
+        _encore_chemical_plant: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_chemical_plant: bool,
+        #[default = true]
+        /// Act 1
+        encore_chemical_plant_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_chemical_plant_2: bool,
+        /// Studiopolis
+        #[heading_level = 1]
+        _encore_studiopolis: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_studiopolis: bool,
+        #[default = true]
+        /// Act 1
+        encore_studiopolis_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_studiopolis_2: bool,
+        /// Flying Battery
+        #[heading_level = 1]
+        _encore_flying_battery: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_flying_battery: bool,
+        #[default = true]
+        /// Act 1
+        encore_flying_battery_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_flying_battery_2: bool,
+        /// Press Garden
+        #[heading_level = 1]
+        _encore_press_garden: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_press_garden: bool,
+        #[default = true]
+        /// Act 1
+        encore_press_garden_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_press_garden_2: bool,
+        /// Stardust Speedway
+        #[heading_level = 1]
+        _encore_stardust_speedway: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_stardust_speedway: bool,
+        #[default = true]
+        /// Act 1
+        encore_stardust_speedway_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_stardust_speedway_2: bool,
+        /// Hydrocity
+        #[heading_level = 1]
+        _encore_hydrocity: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_hydrocity: bool,
+        #[default = true]
+        /// Act 1
+        encore_hydrocity_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_hydrocity_2: bool,
+        /// Mirage Saloon
+        #[heading_level = 1]
+        _encore_mirage_saloon: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_mirage_saloon: bool,
+        #[default = true]
+        /// Act 1
+        encore_mirage_saloon_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_mirage_saloon_2: bool,
+        /// Oil Ocean
+        #[heading_level = 1]
+        _encore_oil_ocean: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_oil_ocean: bool,
+        #[default = true]
+        /// Act 1
+        encore_oil_ocean_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_oil_ocean_2: bool,
+        /// Lava Reef
+        #[heading_level = 1]
+        _encore_lava_reef: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_lava_reef: bool,
+        #[default = true]
+        /// Act 1
+        encore_lava_reef_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_lava_reef_2: bool,
+        /// Metallic Madness
+        #[heading_level = 1]
+        _encore_metallic_madness: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_metallic_madness: bool,
+        #[default = true]
+        /// Act 1
+        encore_metallic_madness_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_metallic_madness_2: bool,
+        /// Titanic Monarch
+        #[heading_level = 1]
+        _encore_titanic_monarch: Title,
+        #[default = true]
+        /// Split on every act of the zone (governs both acts below)
+        encore_titanic_monarch: bool,
+        #[default = true]
+        /// Act 1
+        encore_titanic_monarch_1: bool,
+        #[default = true]
+        /// Act 2
+        encore_titanic_monarch_2: bool,
+        /// Special Stages
+        #[heading_level = 0]
+        _special_stages: Title,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 1
+        chaos_emerald_1: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 2
+        chaos_emerald_2: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 3
+        chaos_emerald_3: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 4
+        chaos_emerald_4: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 5
+        chaos_emerald_5: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 6
+        chaos_emerald_6: bool,
+        #[default = false]
+        /// Split when collecting Chaos Emerald 7
+        chaos_emerald_7: bool,
+        #[default = false]
+        /// Split when failing a Special Stage
+        special_stage_failed: bool,
+        /// Blue Spheres bonus stages
+        #[heading_level = 0]
+        _bonus_stages: Title,
+        #[default = false]
+        /// Split when earning a medal (or upgrading one to gold)
+        medal_earned: bool,
+        #[default = false]
+        /// Split when leaving without a new medal
+        bonus_stage_failed: bool,
+        #[default = false]
+        /// Split whenever leaving, regardless of the result
+        bonus_stage_exited: bool,
+        /// Bosses (Mania and Encore mode)
+        #[heading_level = 0]
+        _bosses: Title,
+        #[default = false]
+        /// Split as soon as the Death Egg Robot (Green Hill Act 2) is defeated
+        boss_green_hill: bool,
+        #[default = false]
+        /// Split as soon as the Mean Bean Machine (Chemical Plant Act 2) is defeated
+        boss_chemical_plant: bool,
+        #[default = false]
+        /// Split as soon as the Weather Mobile (Studiopolis Act 2) is defeated
+        boss_studiopolis: bool,
+        #[default = false]
+        /// Split as soon as the Big Squeeze (Flying Battery Act 2) is defeated
+        boss_flying_battery: bool,
+        #[default = false]
+        /// Split as soon as the Heavy Shinobi (Press Garden Act 2) is defeated
+        boss_press_garden: bool,
+        #[default = false]
+        /// Split as soon as the Metal Sonic (Stardust Speedway Act 2) is defeated
+        boss_stardust_speedway: bool,
+        #[default = false]
+        /// Split as soon as the Laundro Mobile (Hydrocity Act 2) is defeated
+        boss_hydrocity: bool,
+        #[default = false]
+        /// Split as soon as the Heavy Magician (Mirage Saloon Act 2) is defeated
+        boss_mirage_saloon: bool,
+        #[default = false]
+        /// Split as soon as the Mega Octus (Oil Ocean Act 2) is defeated
+        boss_oil_ocean: bool,
+        #[default = false]
+        /// Split as soon as the Heavy Rider (Lava Reef Act 2) is defeated
+        boss_lava_reef: bool,
+        #[default = false]
+        /// Split as soon as the Egg Pistons Mk. II (Metallic Madness Act 2) is defeated
+        boss_metallic_madness: bool,
+        #[default = false]
+        /// Split as soon as the Phantom Egg (Titanic Monarch Act 2) is defeated
+        boss_titanic_monarch: bool,
+        /// Star posts (Mania and Encore mode)
+        #[heading_level = 0]
+        _star_posts: Title,
+        /// Star posts to split on in Green Hill Act 1
+        star_posts_green_hill_1: StarPosts,
+        /// Star posts to split on in Green Hill Act 2
+        star_posts_green_hill_2: StarPosts,
+        /// Star posts to split on in Chemical Plant Act 1
+        star_posts_chemical_plant_1: StarPosts,
+        /// Star posts to split on in Chemical Plant Act 2
+        star_posts_chemical_plant_2: StarPosts,
+        /// Star posts to split on in Studiopolis Act 1
+        star_posts_studiopolis_1: StarPosts,
+        /// Star posts to split on in Studiopolis Act 2
+        star_posts_studiopolis_2: StarPosts,
+        /// Star posts to split on in Flying Battery Act 1
+        star_posts_flying_battery_1: StarPosts,
+        /// Star posts to split on in Flying Battery Act 2
+        star_posts_flying_battery_2: StarPosts,
+        /// Star posts to split on in Press Garden Act 1
+        star_posts_press_garden_1: StarPosts,
+        /// Star posts to split on in Press Garden Act 2
+        star_posts_press_garden_2: StarPosts,
+        /// Star posts to split on in Stardust Speedway Act 1
+        star_posts_stardust_speedway_1: StarPosts,
+        /// Star posts to split on in Stardust Speedway Act 2
+        star_posts_stardust_speedway_2: StarPosts,
+        /// Star posts to split on in Hydrocity Act 1
+        star_posts_hydrocity_1: StarPosts,
+        /// Star posts to split on in Hydrocity Act 2
+        star_posts_hydrocity_2: StarPosts,
+        /// Star posts to split on in Mirage Saloon Act 1
+        star_posts_mirage_saloon_1: StarPosts,
+        /// Star posts to split on in Mirage Saloon Act 2
+        star_posts_mirage_saloon_2: StarPosts,
+        /// Star posts to split on in Oil Ocean Act 1
+        star_posts_oil_ocean_1: StarPosts,
+        /// Star posts to split on in Oil Ocean Act 2
+        star_posts_oil_ocean_2: StarPosts,
+        /// Star posts to split on in Lava Reef Act 1
+        star_posts_lava_reef_1: StarPosts,
+        /// Star posts to split on in Lava Reef Act 2
+        star_posts_lava_reef_2: StarPosts,
+        /// Star posts to split on in Metallic Madness Act 1
+        star_posts_metallic_madness_1: StarPosts,
+        /// Star posts to split on in Metallic Madness Act 2
+        star_posts_metallic_madness_2: StarPosts,
+        /// Star posts to split on in Titanic Monarch Act 1
+        star_posts_titanic_monarch_1: StarPosts,
+        /// Star posts to split on in Titanic Monarch Act 2
+        star_posts_titanic_monarch_2: StarPosts,
+        /// Debug
+        #[heading_level = 0]
+        _debug: Title,
+        #[default = false]
+        /// Print a trace of the values read from the game's memory, to be attached to bug reports
+        debug_trace: bool,
+    }
+}
+
+impl Settings {
+    /// Star posts to split on in the specified act, both in Mania and Encore mode
+    fn star_posts(&self, act: Acts) -> StarPosts {
+        match act {
+            Acts::GreenHill1 | Acts::EncoreGreenHill1 => self.star_posts_green_hill_1,
+            Acts::GreenHill2 | Acts::EncoreGreenHill2 => self.star_posts_green_hill_2,
+            Acts::ChemicalPlant1 | Acts::EncoreChemicalPlant1 => self.star_posts_chemical_plant_1,
+            Acts::ChemicalPlant2 | Acts::EncoreChemicalPlant2 => self.star_posts_chemical_plant_2,
+            Acts::Studiopolis1 | Acts::EncoreStudiopolis1 => self.star_posts_studiopolis_1,
+            Acts::Studiopolis2 | Acts::EncoreStudiopolis2 => self.star_posts_studiopolis_2,
+            Acts::FlyingBattery1 | Acts::EncoreFlyingBattery1 => self.star_posts_flying_battery_1,
+            Acts::FlyingBattery2 | Acts::EncoreFlyingBattery2 => self.star_posts_flying_battery_2,
+            Acts::PressGarden1 | Acts::EncorePressGarden1 => self.star_posts_press_garden_1,
+            Acts::PressGarden2 | Acts::EncorePressGarden2 => self.star_posts_press_garden_2,
+            Acts::StardustSpeedway1 | Acts::EncoreStardustSpeedway1 => {
+                self.star_posts_stardust_speedway_1
+            }
+            Acts::StardustSpeedway2 | Acts::EncoreStardustSpeedway2 => {
+                self.star_posts_stardust_speedway_2
+            }
+            Acts::Hydrocity1 | Acts::EncoreHydrocity1 => self.star_posts_hydrocity_1,
+            Acts::Hydrocity2 | Acts::EncoreHydrocity2 => self.star_posts_hydrocity_2,
+            Acts::MirageSaloon1 | Acts::EncoreMirageSaloon1 => self.star_posts_mirage_saloon_1,
+            Acts::MirageSaloon2 | Acts::EncoreMirageSaloon2 => self.star_posts_mirage_saloon_2,
+            Acts::OilOcean1 | Acts::EncoreOilOcean1 => self.star_posts_oil_ocean_1,
+            Acts::OilOcean2 | Acts::EncoreOilOcean2 => self.star_posts_oil_ocean_2,
+            Acts::LavaReef1 | Acts::EncoreLavaReef1 => self.star_posts_lava_reef_1,
+            Acts::LavaReef2 | Acts::EncoreLavaReef2 => self.star_posts_lava_reef_2,
+            Acts::MetallicMadness1 | Acts::EncoreMetallicMadness1 => {
+                self.star_posts_metallic_madness_1
+            }
+            Acts::MetallicMadness2 | Acts::EncoreMetallicMadness2 => {
+                self.star_posts_metallic_madness_2
+            }
+            Acts::TitanicMonarch1 | Acts::EncoreTitanicMonarch1 => {
+                self.star_posts_titanic_monarch_1
+            }
+            Acts::TitanicMonarch2 | Acts::EncoreTitanicMonarch2 => {
+                self.star_posts_titanic_monarch_2
+            }
+            _ => StarPosts::None,
+        }
+    }
+
+    /// Whether splitting is enabled when the specified boss is defeated
+    fn boss_split_enabled(&self, boss: Boss) -> bool {
+        match boss {
+            Boss::GreenHill => self.boss_green_hill,
+            Boss::ChemicalPlant => self.boss_chemical_plant,
+            Boss::Studiopolis => self.boss_studiopolis,
+            Boss::FlyingBattery => self.boss_flying_battery,
+            Boss::PressGarden => self.boss_press_garden,
+            Boss::StardustSpeedway => self.boss_stardust_speedway,
+            Boss::Hydrocity => self.boss_hydrocity,
+            Boss::MirageSaloon => self.boss_mirage_saloon,
+            Boss::OilOcean => self.boss_oil_ocean,
+            Boss::LavaReef => self.boss_lava_reef,
+            Boss::MetallicMadness => self.boss_metallic_madness,
+            Boss::TitanicMonarch => self.boss_titanic_monarch,
+        }
+    }
+
+    /// Whether splitting is enabled when collecting the emerald with the specified bit in the bitmask
+    fn chaos_emerald_split_enabled(&self, bit: u32) -> bool {
+        match bit {
+            0 => self.chaos_emerald_1,
+            1 => self.chaos_emerald_2,
+            2 => self.chaos_emerald_3,
+            3 => self.chaos_emerald_4,
+            4 => self.chaos_emerald_5,
+            5 => self.chaos_emerald_6,
+            6 => self.chaos_emerald_7,
+            _ => false,
+        }
+    }
+
+    /// Whether splitting is enabled when leaving the specified act, including the toggles of its mode and zone
+    fn split_enabled(&self, act: Acts) -> bool {
+        let (mode, zone, act) = match act {
+            Acts::GreenHill1 => (self.mania_mode, self.mania_green_hill, self.green_hill_1),
+            Acts::GreenHill2 => (self.mania_mode, self.mania_green_hill, self.green_hill_2),
+            Acts::ChemicalPlant1 => (
+                self.mania_mode,
+                self.mania_chemical_plant,
+                self.chemical_plant_1,
+            ),
+            Acts::ChemicalPlant2 => (
+                self.mania_mode,
+                self.mania_chemical_plant,
+                self.chemical_plant_2,
+            ),
+            Acts::Studiopolis1 => (self.mania_mode, self.mania_studiopolis, self.studiopolis_1),
+            Acts::Studiopolis2 => (self.mania_mode, self.mania_studiopolis, self.studiopolis_2),
+            Acts::FlyingBattery1 => (
+                self.mania_mode,
+                self.mania_flying_battery,
+                self.flying_battery_1,
+            ),
+            Acts::FlyingBattery2 => (
+                self.mania_mode,
+                self.mania_flying_battery,
+                self.flying_battery_2,
+            ),
+            Acts::PressGarden1 => (
+                self.mania_mode,
+                self.mania_press_garden,
+                self.press_garden_1,
+            ),
+            Acts::PressGarden2 => (
+                self.mania_mode,
+                self.mania_press_garden,
+                self.press_garden_2,
+            ),
+            Acts::StardustSpeedway1 => (
+                self.mania_mode,
+                self.mania_stardust_speedway,
+                self.stardust_speedway_1,
+            ),
+            Acts::StardustSpeedway2 => (
+                self.mania_mode,
+                self.mania_stardust_speedway,
+                self.stardust_speedway_2,
+            ),
+            Acts::Hydrocity1 => (self.mania_mode, self.mania_hydrocity, self.hydrocity_1),
+            Acts::Hydrocity2 => (self.mania_mode, self.mania_hydrocity, self.hydrocity_2),
+            Acts::MirageSaloon1 => (
+                self.mania_mode,
+                self.mania_mirage_saloon,
+                self.mirage_saloon_1,
+            ),
+            Acts::MirageSaloon2 => (
+                self.mania_mode,
+                self.mania_mirage_saloon,
+                self.mirage_saloon_2,
+            ),
+            Acts::OilOcean1 => (self.mania_mode, self.mania_oil_ocean, self.oil_ocean_1),
+            Acts::OilOcean2 => (self.mania_mode, self.mania_oil_ocean, self.oil_ocean_2),
+            Acts::LavaReef1 => (self.mania_mode, self.mania_lava_reef, self.lava_reef_1),
+            Acts::LavaReef2 => (self.mania_mode, self.mania_lava_reef, self.lava_reef_2),
+            Acts::MetallicMadness1 => (
+                self.mania_mode,
+                self.mania_metallic_madness,
+                self.metallic_madness_1,
+            ),
+            Acts::MetallicMadness2 => (
+                self.mania_mode,
+                self.mania_metallic_madness,
+                self.metallic_madness_2,
+            ),
+            Acts::TitanicMonarch1 => (
+                self.mania_mode,
+                self.mania_titanic_monarch,
+                self.titanic_monarch_1,
+            ),
+            Acts::TitanicMonarch2 => (
+                self.mania_mode,
+                self.mania_titanic_monarch,
+                self.titanic_monarch_2,
+            ),
+            Acts::EggReverie => (self.mania_mode, true, self.egg_reverie),
+            Acts::EncoreAngelIsland => (self.encore_mode, true, self.angel_island),
+            Acts::EncoreGreenHill1 => (
+                self.encore_mode,
+                self.encore_green_hill,
+                self.encore_green_hill_1,
+            ),
+            Acts::EncoreGreenHill2 => (
+                self.encore_mode,
+                self.encore_green_hill,
+                self.encore_green_hill_2,
+            ),
+            Acts::EncoreChemicalPlant1 => (
+                self.encore_mode,
+                self.encore_chemical_plant,
+                self.encore_chemical_plant_1,
+            ),
+            Acts::EncoreChemicalPlant2 => (
+                self.encore_mode,
+                self.encore_chemical_plant,
+                self.encore_chemical_plant_2,
+            ),
+            Acts::EncoreStudiopolis1 => (
+                self.encore_mode,
+                self.encore_studiopolis,
+                self.encore_studiopolis_1,
+            ),
+            Acts::EncoreStudiopolis2 => (
+                self.encore_mode,
+                self.encore_studiopolis,
+                self.encore_studiopolis_2,
+            ),
+            Acts::EncoreFlyingBattery1 => (
+                self.encore_mode,
+                self.encore_flying_battery,
+                self.encore_flying_battery_1,
+            ),
+            Acts::EncoreFlyingBattery2 => (
+                self.encore_mode,
+                self.encore_flying_battery,
+                self.encore_flying_battery_2,
+            ),
+            Acts::EncorePressGarden1 => (
+                self.encore_mode,
+                self.encore_press_garden,
+                self.encore_press_garden_1,
+            ),
+            Acts::EncorePressGarden2 => (
+                self.encore_mode,
+                self.encore_press_garden,
+                self.encore_press_garden_2,
+            ),
+            Acts::EncoreStardustSpeedway1 => (
+                self.encore_mode,
+                self.encore_stardust_speedway,
+                self.encore_stardust_speedway_1,
+            ),
+            Acts::EncoreStardustSpeedway2 => (
+                self.encore_mode,
+                self.encore_stardust_speedway,
+                self.encore_stardust_speedway_2,
+            ),
+            Acts::EncoreHydrocity1 => (
+                self.encore_mode,
+                self.encore_hydrocity,
+                self.encore_hydrocity_1,
+            ),
+            Acts::EncoreHydrocity2 => (
+                self.encore_mode,
+                self.encore_hydrocity,
+                self.encore_hydrocity_2,
+            ),
+            Acts::EncoreMirageSaloon1 => (
+                self.encore_mode,
+                self.encore_mirage_saloon,
+                self.encore_mirage_saloon_1,
+            ),
+            Acts::EncoreMirageSaloon2 => (
+                self.encore_mode,
+                self.encore_mirage_saloon,
+                self.encore_mirage_saloon_2,
+            ),
+            Acts::EncoreOilOcean1 => (
+                self.encore_mode,
+                self.encore_oil_ocean,
+                self.encore_oil_ocean_1,
+            ),
+            Acts::EncoreOilOcean2 => (
+                self.encore_mode,
+                self.encore_oil_ocean,
+                self.encore_oil_ocean_2,
+            ),
+            Acts::EncoreLavaReef1 => (
+                self.encore_mode,
+                self.encore_lava_reef,
+                self.encore_lava_reef_1,
+            ),
+            Acts::EncoreLavaReef2 => (
+                self.encore_mode,
+                self.encore_lava_reef,
+                self.encore_lava_reef_2,
+            ),
+            Acts::EncoreMetallicMadness1 => (
+                self.encore_mode,
+                self.encore_metallic_madness,
+                self.encore_metallic_madness_1,
+            ),
+            Acts::EncoreMetallicMadness2 => (
+                self.encore_mode,
+                self.encore_metallic_madness,
+                self.encore_metallic_madness_2,
+            ),
+            Acts::EncoreTitanicMonarch1 => (
+                self.encore_mode,
+                self.encore_titanic_monarch,
+                self.encore_titanic_monarch_1,
+            ),
+            Acts::EncoreTitanicMonarch2 => (
+                self.encore_mode,
+                self.encore_titanic_monarch,
+                self.encore_titanic_monarch_2,
+            ),
+        };
+        mode && zone && act
+    }
+}
+
+struct Addresses {
+    variant: GameVariant,
+    layout: &'static Layout,
+    main_module_name: &'static str,
+    modules: Modules,
+    /// Whether the game logic lives in Game.dll, which can be reloaded at any time by mod loaders
+    uses_game_dll: bool,
+    game_dll_loaded: bool,
+    /// Ticks left before checking whether Game.dll is still there, or has shown up
+    game_dll_check_ticks: u8,
+    /// Scan of the reloaded Game.dll, still looking for some of the targets
+    game_scan: Option<PendingScan<21>>,
+    game: GameAddresses,
+    start_trigger: Address,
+    mania_mode_selection: Address,
+    save_selection_mania: Address,
+    save_0: Address,
+    save_1: Address,
+    save_2: Address,
+    save_3: Address,
+    save_4: Address,
+    save_5: Address,
+    save_6: Address,
+    save_7: Address,
+    save_selection_encore: Address,
+    encore_save_1: Address,
+    encore_save_2: Address,
+    encore_save_3: Address,
+    /// Signatures not found in the main module, which never gets reloaded
+    missing_main_signatures: ArrayString<256>,
+    /// Signatures not found in the game module, scanned for again every time it gets reloaded
+    missing_game_signatures: ArrayString<256>,
+}
+
+/// Addresses found in the game module. On the decomp they need to be found again every time
+/// Game.dll gets reloaded.
+struct GameAddresses {
+    level_id_base: Address,
+    level_id_offset: u32,
+    status_base: Address,
+    status_offset: u32,
+    egg_reverie_base: Address,
+    egg_reverie1_offset1: u32,
+    egg_reverie1_offset2: u32,
+    egg_reverie2_offset1: u32,
+    egg_reverie2_offset2: u32,
+    egg_monarch_base: Address,
+    egg_monarch_offset: u32,
+    chaos_emerald_base: Address,
+    chaos_emerald_offset1: u32,
+    chaos_emerald_offset2: u32,
+    character_base: Address,
+    character_offset: u32,
+    results_screen_base: Address,
+    results_screen_offset: u32,
+    /// Health of the Act 2 bosses, indexed by [`Boss`]. Left unresolved where it can't be read.
+    boss_health: [Resolved; 12],
+}
+
+enum GameModule {
+    Unchanged,
+    Reloaded,
+    /// Still looking for the addresses in the reloaded Game.dll
+    Scanning,
+    Unloaded,
+}
+
+impl Addresses {
+    async fn init(process: &Process) -> Self {
+        let (main_module_name, main_module) = retry(|| {
+            PROCESS_NAMES.iter().find_map(|&name| {
+                let base = process.get_module_address(name).ok()?;
+                let size = pe::read_size_of_image(process, base)? as u64;
+                Some((name, (base, size)))
+            })
+        })
+        .await;
+
+        let is_64_bit =
+            retry(|| pe::MachineType::read(process, main_module.0)).await == MachineType::X86_64;
+
+        // Try to find the Game.dll module. If it succeeds, it means we are running on the decompilation,
+        // where most of the sigscanning will be performed in the Game.dll module.
+        // In the retail version, we need only the main module.
+        let game_module = match process.get_module_address("Game.dll") {
+            Ok(base) => (
+                base,
+                retry(|| pe::read_size_of_image(process, base)).await as u64,
+            ),
+            Err(_) => main_module,
+        };
+
+        let modules = Modules {
+            main: main_module,
+            game: game_module,
+        };
+        let uses_game_dll = modules.game != modules.main;
+        let variant = GameVariant::detect(process, &modules, main_module_name, is_64_bit);
+        let layout = Layout::find(variant);
+
+        // Targets that can't be found are left unresolved: reading from them simply fails, while
+        // everything else keeps working
+        let mut scanner = Scanner::new(process, &modules);
+        let game = GameAddresses::resolve(layout, &mut scanner).await;
+        let missing_game_signatures = core::mem::take(&mut scanner.missing);
+        let save_data = scanner.resolve(&layout.targets.save_data).await;
+        let missing_main_signatures = scanner.missing;
+
+        let saves = &layout.saves;
+        let save_address = |offset: u32| match &save_data {
+            Some(save_data) => save_data.address + offset,
+            None => Address::NULL,
+        };
+        let save_mania = |slot: u32| save_address(saves.mania_slots + saves.slot_size * slot);
+        let save_encore = |slot: u32| save_address(saves.encore_slots + saves.slot_size * slot);
+
+        Self {
+            variant,
+            layout,
+            main_module_name,
+            uses_game_dll,
+            game_dll_loaded: uses_game_dll,
+            game_dll_check_ticks: GAME_DLL_CHECK_TICKS,
+            game_scan: None,
+            game,
+            start_trigger: save_address(saves.start_trigger),
+            mania_mode_selection: save_address(saves.mania_mode_selection),
+            save_selection_mania: save_address(saves.save_selection_mania),
+            save_0: save_mania(0),
+            save_1: save_mania(1),
+            save_2: save_mania(2),
+            save_3: save_mania(3),
+            save_4: save_mania(4),
+            save_5: save_mania(5),
+            save_6: save_mania(6),
+            save_7: save_mania(7),
+            save_selection_encore: save_address(saves.save_selection_encore),
+            encore_save_1: save_encore(1),
+            encore_save_2: save_encore(2),
+            encore_save_3: save_encore(3),
+            missing_main_signatures,
+            missing_game_signatures,
+            modules,
+        }
+    }
+
+    /// Checks whether Game.dll has been loaded, unloaded or reloaded at a different address since
+    /// the last scan. Only the addresses found in it get scanned for again, over as many ticks as
+    /// the game needs to finish loading it.
+    fn update_game_module(&mut self, process: &Process) -> GameModule {
+        if self.game_scan.is_some() {
+            return self.poll_game_scan(process);
+        }
+
+        if self.game_dll_check_ticks > 0 {
+            self.game_dll_check_ticks -= 1;
+            return match self.game_dll_loaded || !self.uses_game_dll {
+                true => GameModule::Unchanged,
+                false => GameModule::Unloaded,
+            };
+        }
+        self.game_dll_check_ticks = GAME_DLL_CHECK_TICKS;
+
+        let game_dll = process
+            .get_module_address("Game.dll")
+            .ok()
+            .and_then(|base| Some((base, pe::read_size_of_image(process, base)? as u64)));
+        let Some(game_dll) = game_dll else {
+            // Without Game.dll ever showing up, the game logic is in the main module
+            if !self.uses_game_dll {
+                return GameModule::Unchanged;
+            }
+            self.game_dll_loaded = false;
+            return GameModule::Unloaded;
+        };
+
+        if self.game_dll_loaded && game_dll.0 == self.modules.game.0 {
+            return GameModule::Unchanged;
+        }
+
+        self.modules.game = game_dll;
+        self.game_dll_loaded = true;
+        if !self.uses_game_dll {
+            // Game.dll got loaded after we attached, so it can only be the decomp
+            self.uses_game_dll = true;
+            self.variant = GameVariant::detect(
+                process,
+                &self.modules,
+                self.main_module_name,
+                self.variant.is_64_bit,
+            );
+        }
+        self.game_scan = Some(PendingScan::new());
+        self.poll_game_scan(process)
+    }
+
+    fn poll_game_scan(&mut self, process: &Process) -> GameModule {
+        let Some(scan) = &mut self.game_scan else {
+            return GameModule::Unchanged;
+        };
+        let Some(resolved) = scan.poll(process, &self.modules, self.layout.game_targets()) else {
+            return GameModule::Scanning;
+        };
+
+        self.game = GameAddresses::new(resolved);
+        self.missing_game_signatures = scan.missing;
+        self.game_scan = None;
+        GameModule::Reloaded
+    }
+
+    /// Reports the detected version of the game and the signatures we couldn't find
+    fn report(&self) {
+        runtime::set_variable("Game version", &self.variant.name());
+
+        let mut missing = ArrayString::<512>::new();
+        for signatures in [&self.missing_game_signatures, &self.missing_main_signatures] {
+            if !signatures.is_empty() {
+                if !missing.is_empty() {
+                    let _ = missing.try_push_str(", ");
+                }
+                let _ = missing.try_push_str(signatures);
+            }
+        }
+        runtime::set_variable(
+            "Missing signatures",
+            match missing.as_str() {
+                "" => "None",
+                missing => missing,
+            },
+        );
+    }
+}
+
+impl GameAddresses {
+    async fn resolve(layout: &Layout, scanner: &mut Scanner<'_>) -> Self {
+        let mut resolved: [Resolved; 21] = Default::default();
+        for (resolved, target) in resolved.iter_mut().zip(layout.game_targets()) {
+            if let Some(target) = target {
+                *resolved = scanner.resolve(target).await.unwrap_or_default();
+            }
+        }
+        Self::new(resolved)
+    }
+
+    /// Takes the targets in the order of [`Layout::game_targets`]
+    fn new(
+        [level_id, status, egg_reverie_1, egg_reverie_2, egg_monarch, egg_monarch_offset, chaos_emeralds, characters, results_screen, boss_health @ ..]: [Resolved; 21],
+    ) -> Self {
+        Self {
+            level_id_base: level_id.address,
+            level_id_offset: level_id.values[0],
+            status_base: status.address,
+            status_offset: status.values[0],
+            egg_reverie_base: egg_reverie_1.address,
+            egg_reverie1_offset1: egg_reverie_1.values[0],
+            egg_reverie1_offset2: egg_reverie_1.values[1],
+            egg_reverie2_offset1: egg_reverie_2.values[0],
+            egg_reverie2_offset2: egg_reverie_2.values[1],
+            egg_monarch_base: egg_monarch.address,
+            egg_monarch_offset: egg_monarch_offset.values[0],
+            chaos_emerald_base: chaos_emeralds.address,
+            chaos_emerald_offset1: chaos_emeralds.values[0],
+            chaos_emerald_offset2: chaos_emeralds.values[1],
+            character_base: characters.address,
+            character_offset: characters.values[0],
+            results_screen_base: results_screen.address,
+            results_screen_offset: results_screen.values[0],
+            boss_health,
+        }
+    }
+}
+
+/// Raw values read from the game's memory on every tick.
+/// Keeping them separate from the watchers allows to feed the splitting logic with recorded data.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Snapshot {
+    game_mode: u8,
+    level_id: u8,
+    status: [u8; 5],
+    egg_reverie_monarch_health: u8,
+    egg_reverie_eggman_health: u8,
+    tm2_defeated: u8,
+    /// Health of the Act 2 boss of the act being played, where it can be read
+    boss_health: Option<u8>,
+    /// Whether the results screen is shown, where it can be read
+    results_screen: Option<bool>,
+    chaos_emeralds: u8,
+    characters: [u8; 2],
+    stock: [u8; 4],
+    medals: [u8; 32],
+    star_post: u32,
+    start_trigger: u32,
+    mania_mode_selection: u8,
+    save_selection_mania: u8,
+    saves_mania: [u8; 8],
+    save_selection_encore: u8,
+    saves_encore: [u8; 3],
+    /// State, character and zone of the save slot selected in the menu
+    save_progress: [u8; 3],
+    /// Leader and buddy of the Encore mode save slot selected in the menu
+    save_team: [u8; 2],
+}
+
+impl Snapshot {
+    fn read(game: &Process, addresses: &Addresses) -> Self {
+        let game_mode: u8;
+        let levelid: u8;
+        let status: [u8; 5];
+        let egg_reverie_monarch_health: u8;
+        let egg_reverie_eggman_health: u8;
+        let tm2_defeat: u8;
+        let chaos_emeralds: u8;
+        let characters: [u8; 2];
+        let stock: [u8; 4];
+        let medals: [u32; 32];
+        let star_post: u32;
+        let globals = &addresses.layout.globals;
+
+        if addresses.variant.is_64_bit {
+            game_mode = game
+                .read_pointer_path64(addresses.game.character_base, &[0, 0])
+                .ok()
+                .unwrap_or_default();
+            levelid = game
+                .read_pointer_path64(
+                    addresses.game.level_id_base,
+                    &[0, addresses.game.level_id_offset as u64],
+                )
+                .ok()
+                .unwrap_or_default();
+            status = game
+                .read_pointer_path64(
+                    addresses.game.status_base,
+                    &[0, addresses.game.status_offset as u64],
+                )
+                .ok()
+                .unwrap_or_default();
+            egg_reverie_monarch_health = game
+                .read_pointer_path64(
+                    addresses.game.egg_reverie_base,
+                    &[
+                        0,
+                        addresses.game.egg_reverie1_offset1 as u64,
+                        addresses.game.egg_reverie1_offset2 as u64,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            egg_reverie_eggman_health = game
+                .read_pointer_path64(
+                    addresses.game.egg_reverie_base,
+                    &[
+                        0,
+                        addresses.game.egg_reverie2_offset1 as u64,
+                        addresses.game.egg_reverie2_offset2 as u64,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            tm2_defeat = game
+                .read_pointer_path64(
+                    addresses.game.egg_monarch_base,
+                    &[0, addresses.game.egg_monarch_offset as u64],
+                )
+                .ok()
+                .unwrap_or_default();
+            chaos_emeralds = game
+                .read_pointer_path64(
+                    addresses.game.chaos_emerald_base,
+                    &[
+                        0,
+                        addresses.game.chaos_emerald_offset1 as u64,
+                        addresses.game.chaos_emerald_offset2 as u64,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            characters = game
+                .read_pointer_path64(
+                    addresses.game.character_base,
+                    &[0, addresses.game.character_offset as u64],
+                )
+                .ok()
+                .unwrap_or_default();
+            stock = game
+                .read_pointer_path64(addresses.game.character_base, &[0, globals.stock as u64])
+                .ok()
+                .unwrap_or_default();
+            medals = game
+                .read_pointer_path64(addresses.game.character_base, &[0, globals.medals as u64])
+                .ok()
+                .unwrap_or_default();
+            star_post = game
+                .read_pointer_path64(
+                    addresses.game.character_base,
+                    &[0, globals.star_post as u64],
+                )
+                .ok()
+                .unwrap_or_default();
+        } else {
+            game_mode = game
+                .read_pointer_path32(addresses.game.character_base, &[0, 0])
+                .ok()
+                .unwrap_or_default();
+            levelid = game
+                .read_pointer_path32(
+                    addresses.game.level_id_base,
+                    &[0, addresses.game.level_id_offset],
+                )
+                .ok()
+                .unwrap_or_default();
+            status = game
+                .read_pointer_path32(
+                    addresses.game.status_base,
+                    &[0, addresses.game.status_offset],
+                )
+                .ok()
+                .unwrap_or_default();
+            egg_reverie_monarch_health = game
+                .read_pointer_path32(
+                    addresses.game.egg_reverie_base,
+                    &[
+                        0,
+                        addresses.game.egg_reverie1_offset1,
+                        addresses.game.egg_reverie1_offset2,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            egg_reverie_eggman_health = game
+                .read_pointer_path32(
+                    addresses.game.egg_reverie_base,
+                    &[
+                        0,
+                        addresses.game.egg_reverie2_offset1,
+                        addresses.game.egg_reverie2_offset2,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            tm2_defeat = game
+                .read_pointer_path32(
+                    addresses.game.egg_monarch_base,
+                    &[0, addresses.game.egg_monarch_offset],
+                )
+                .ok()
+                .unwrap_or_default();
+            chaos_emeralds = game
+                .read_pointer_path32(
+                    addresses.game.chaos_emerald_base,
+                    &[
+                        0,
+                        addresses.game.chaos_emerald_offset1,
+                        addresses.game.chaos_emerald_offset2,
+                    ],
+                )
+                .ok()
+                .unwrap_or_default();
+            characters = game
+                .read_pointer_path32(
+                    addresses.game.character_base,
+                    &[0, addresses.game.character_offset],
+                )
+                .ok()
+                .unwrap_or_default();
+            stock = game
+                .read_pointer_path32(addresses.game.character_base, &[0, globals.stock])
+                .ok()
+                .unwrap_or_default();
+            medals = game
+                .read_pointer_path32(addresses.game.character_base, &[0, globals.medals])
+                .ok()
+                .unwrap_or_default();
+            star_post = game
+                .read_pointer_path32(addresses.game.character_base, &[0, globals.star_post])
+                .ok()
+                .unwrap_or_default();
+        }
+
+        let results_screen = if addresses.variant.is_64_bit {
+            game.read_pointer_path64::<u8>(
+                addresses.game.results_screen_base,
+                &[0, addresses.game.results_screen_offset as u64],
+            )
+        } else {
+            game.read_pointer_path32::<u8>(
+                addresses.game.results_screen_base,
+                &[0, addresses.game.results_screen_offset],
+            )
+        }
+        .ok()
+        .map(|results_screen| results_screen != 0);
+
+        // Only the boss of the act being played is looked at
+        let boss_health = Acts::from_level_id(game_mode, levelid)
+            .and_then(Boss::from_act)
+            .and_then(|boss| {
+                let health = &addresses.game.boss_health[boss as usize];
+                if addresses.variant.is_64_bit {
+                    game.read_pointer_path64(
+                        health.address,
+                        &[0, health.values[0] as u64, health.values[1] as u64],
+                    )
+                } else {
+                    game.read_pointer_path32(
+                        health.address,
+                        &[0, health.values[0], health.values[1]],
+                    )
+                }
+                .ok()
+            });
+
+        let mania_mode_selection: u8 = game
+            .read(addresses.mania_mode_selection)
+            .ok()
+            .unwrap_or_default();
+        let save_selection_mania: u8 = game
+            .read(addresses.save_selection_mania)
+            .ok()
+            .unwrap_or_default();
+        let save_selection_encore: u8 = game
+            .read(addresses.save_selection_encore)
+            .ok()
+            .unwrap_or_default();
+
+        // The save selections count the slots from 0 in Mania mode (8 = no save) and from 1 in Encore mode
+        let save_slot = if mania_mode_selection != 0 {
+            (save_selection_mania < 8).then_some(save_selection_mania as u32)
+        } else {
+            (1..=3)
+                .contains(&save_selection_encore)
+                .then(|| globals.encore_save_slots + save_selection_encore as u32 - 1)
+        };
+        let save_offset = save_slot.map(|slot| globals.save_slots + slot * globals.save_slot_size);
+        let save_progress: [u32; 3] = match save_offset {
+            Some(offset) => if addresses.variant.is_64_bit {
+                game.read_pointer_path64(addresses.game.character_base, &[0, offset as u64])
+            } else {
+                game.read_pointer_path32(addresses.game.character_base, &[0, offset])
+            }
+            .ok()
+            .unwrap_or_default(),
+            None => Default::default(),
+        };
+        // Encore mode saves store the whole team instead of a single character
+        let save_team: [u8; 2] = match save_offset.filter(|_| mania_mode_selection == 0) {
+            Some(offset) => {
+                let offset = offset + globals.save_team;
+                if addresses.variant.is_64_bit {
+                    game.read_pointer_path64(addresses.game.character_base, &[0, offset as u64])
+                } else {
+                    game.read_pointer_path32(addresses.game.character_base, &[0, offset])
+                }
+                .ok()
+                .unwrap_or_default()
+            }
+            None => Default::default(),
+        };
+
+        Self {
+            game_mode,
+            level_id: levelid,
+            status,
+            egg_reverie_monarch_health,
+            egg_reverie_eggman_health,
+            tm2_defeated: tm2_defeat,
+            boss_health,
+            results_screen,
+            chaos_emeralds,
+            characters,
+            stock,
+            medals: medals.map(|medal| medal as u8),
+            star_post,
+            start_trigger: game.read(addresses.start_trigger).ok().unwrap_or_default(),
+            mania_mode_selection,
+            save_selection_mania,
+            saves_mania: [
+                game.read(addresses.save_0).ok().unwrap_or_default(),
+                game.read(addresses.save_1).ok().unwrap_or_default(),
+                game.read(addresses.save_2).ok().unwrap_or_default(),
+                game.read(addresses.save_3).ok().unwrap_or_default(),
+                game.read(addresses.save_4).ok().unwrap_or_default(),
+                game.read(addresses.save_5).ok().unwrap_or_default(),
+                game.read(addresses.save_6).ok().unwrap_or_default(),
+                game.read(addresses.save_7).ok().unwrap_or_default(),
+            ],
+            save_selection_encore,
+            saves_encore: [
+                game.read(addresses.encore_save_1).ok().unwrap_or_default(),
+                game.read(addresses.encore_save_2).ok().unwrap_or_default(),
+                game.read(addresses.encore_save_3).ok().unwrap_or_default(),
+            ],
+            save_progress: save_progress.map(|value| value as u8),
+            save_team,
+        }
+    }
+}
+
+impl Watchers {
+    fn update(&mut self, snapshot: &Snapshot) {
+        let game_mode = snapshot.game_mode;
+        let levelid = snapshot.level_id;
+        let status = snapshot.status;
+        let mut enum_levelid: Acts;
+
+        // Level ID logic
+        enum_levelid = match &self.level_id.pair {
+            Some(lvl) => lvl.current,
+            _ => Acts::GreenHill1,
+        };
+
+        // If level ID == 37, it's always Egg Reverie. Don't even consider the IGT because it's scrambled in that stage
+        if levelid == 37 {
+            enum_levelid = Acts::EggReverie;
+        } else if status[0] == 2 || (status[0] != 0 && status[2] + status[3] + status[4] == 0) {
+            if let Some(act) = Acts::from_level_id(game_mode, levelid) {
+                enum_levelid = act;
+            }
+        }
+        self.level_id.update(Some(enum_levelid));
+
+        // The level ID can briefly bounce back to the previous act (eg. when dying at a boss, or swapping
+        // characters in Encore mode), so only reaching a new act counts as progress.
+        // Returning to the main menu means a new run is about to start.
+        if levelid == 2 {
+            self.furthest_act = 0;
+            self.played_acts.clear();
+        }
+        self.progressed = false;
+        self.went_back = false;
+        self.new_act = false;
+        self.ticks_since_progress = self.ticks_since_progress.saturating_add(1);
+        let in_act = Acts::from_level_id(game_mode, levelid).is_some();
+        if let Some(level_id) = self.level_id.pair.filter(|_| in_act) {
+            let played = self.played_acts.contains(&level_id.current);
+            if level_id.changed() {
+                self.new_act = !played;
+                self.replaying_act = played;
+            }
+            if !played {
+                let _ = self.played_acts.try_push(level_id.current);
+            }
+        }
+        if let Some(level_id) = self.level_id.pair.filter(|level_id| level_id.changed()) {
+            if let Some(position) = route::position(level_id.current) {
+                if position > self.furthest_act {
+                    self.furthest_act = position;
+                    self.progressed = true;
+                    self.left_cleared_act =
+                        self.act_cleared.pair.is_some_and(|cleared| cleared.current);
+                    self.ticks_since_progress = 0;
+                } else if position + 1 == self.furthest_act
+                    && self.ticks_since_progress < FALSE_SPLIT_TICKS
+                {
+                    self.went_back = true;
+                }
+            }
+        }
+
+        // In-game timer. Outside of the acts (eg. in the menus) the values are meaningless, so we just ignore them
+        self.in_act.update(Some(in_act));
+        let igt = if in_act {
+            Duration::minutes(status[4] as _)
+                + Duration::seconds(status[3] as _)
+                + Duration::milliseconds(status[2] as i64 * 10)
+        } else {
+            Duration::ZERO
+        };
+
+        // The act timer stops as soon as the act is cleared (signpost, capsule, boss defeated, goal in Time Attack).
+        // It also stops when dying, so it needs to stay stopped for longer than the death animation before we can be sure.
+        // The timer freezing at 9:59.99 is a Time Over, which is a death as well.
+        let time_over = status[4] == 9 && status[3] == 59 && status[2] == 99;
+        // The timer only moves on every other tick, as the game runs at half our tick rate
+        let act_timer_was_running = self.igt_frozen_ticks <= 1;
+        self.igt_frozen_ticks = match &self.igt.pair {
+            Some(old_igt) if status[0] == 1 && old_igt.current == igt && !time_over => {
+                self.igt_frozen_ticks.saturating_add(1)
+            }
+            _ => 0,
+        };
+        self.igt.update(Some(igt));
+        self.star_post.update(Some(snapshot.star_post));
+        // The game only keeps the entity slot of the last star post, so we number them in the order they are first
+        // touched in the act. Star posts are laid out along the act, so this matches their position in it as long
+        // as none of them gets skipped. Going back to an earlier one or respawning from it doesn't count again.
+        if self
+            .level_id_numeric
+            .pair
+            .is_some_and(|level| level.current != levelid)
+        {
+            self.star_posts_touched.clear();
+        }
+        if in_act
+            && self
+                .star_post
+                .pair
+                .is_some_and(|star_post| star_post.changed() && star_post.current != 0)
+            && !self.star_posts_touched.contains(&snapshot.star_post)
+        {
+            let _ = self.star_posts_touched.try_push(snapshot.star_post);
+        }
+        self.star_posts_reached
+            .update(Some(self.star_posts_touched.len() as u8));
+        // Where the results screen can be read, it tells right away when the act is cleared, and a Game Over
+        // stopping the timer for longer than the results screen can't be mistaken for it
+        self.act_clear.update(Some(match snapshot.results_screen {
+            Some(results_screen) => in_act && results_screen,
+            None => in_act && igt != Duration::ZERO && self.igt_frozen_ticks >= ACT_CLEAR_TICKS,
+        }));
+        // A cleared act is never reloaded, so the timer going backwards in the same stage means we're playing it again
+        let reloaded = self.igt.pair.is_some_and(|igt| igt.current < igt.old)
+            && self
+                .level_id_numeric
+                .pair
+                .is_some_and(|level| level.current == levelid);
+        let act_cleared = match (&self.level_id.pair, &self.act_cleared.pair) {
+            (Some(level_id), _) if level_id.changed() => false,
+            _ if reloaded => false,
+            (_, Some(cleared)) if cleared.current => true,
+            _ => self.act_clear.pair.is_some_and(|clear| clear.current),
+        };
+        self.act_cleared.update(Some(act_cleared));
+
+        self.game_mode.update(Some(match game_mode {
+            0 => GameMode::Standard,
+            1 => GameMode::Encore,
+            2 => GameMode::TimeAttack,
+            _ => GameMode::Invalid,
+        }));
+        self.level_id_numeric.update(Some(levelid));
+        self.status.update(Some(status[0]));
+        self.egg_reverie_health.update(Some(
+            snapshot.egg_reverie_eggman_health + snapshot.egg_reverie_monarch_health,
+        ));
+        self.titanic_monarch_2_defeated
+            .update(Some(snapshot.tm2_defeated != 0));
+        self.chaos_emeralds.update(Some(snapshot.chaos_emeralds));
+
+        // The boss is defeated when its health drops to zero with the act timer running up to then: after dying,
+        // the health can read as zero as well once the act reloads, long after the timer stopped. Where the health of
+        // the boss can't be read, Act 2 ending as soon as the boss is defeated means the act clear tells us when it
+        // happens. Titanic Monarch has its own flag, as the act keeps going in the good ending.
+        self.boss_health.update(Some(snapshot.boss_health));
+        let boss_health_depleted = self.boss_health.pair.is_some_and(|health| {
+            health.old.is_some_and(|old| old > 0) && health.current == Some(0)
+        }) && act_timer_was_running;
+        let boss_state = match Boss::from_act(enum_levelid) {
+            None => BossState::None,
+            Some(boss) => {
+                let already_defeated = self
+                    .boss
+                    .pair
+                    .is_some_and(|state| state.current == BossState::Defeated(boss));
+                let defeated = if boss == Boss::TitanicMonarch {
+                    snapshot.tm2_defeated != 0
+                } else if snapshot.boss_health.is_some() {
+                    boss_health_depleted
+                } else {
+                    self.act_clear.pair.is_some_and(|clear| clear.current)
+                };
+
+                if already_defeated || defeated {
+                    BossState::Defeated(boss)
+                } else {
+                    BossState::Fighting(boss)
+                }
+            }
+        };
+        self.boss.update(Some(boss_state));
+
+        // Remember which emeralds we had when entering the Special Stage, so we can tell if it's been failed on exit
+        let in_special_stage = SPECIAL_STAGES.contains(&levelid);
+        if in_special_stage
+            && !self
+                .in_special_stage
+                .pair
+                .is_some_and(|stage| stage.current)
+        {
+            self.emeralds_on_entry = snapshot.chaos_emeralds;
+        }
+        self.in_special_stage.update(Some(in_special_stage));
+
+        // Same for the Blue Spheres bonus stages and their medals
+        let medals = Medals::count(&snapshot.medals);
+        let in_bonus_stage = BONUS_STAGES.contains(&levelid);
+        if in_bonus_stage && !self.in_bonus_stage.pair.is_some_and(|stage| stage.current) {
+            self.medals_on_entry = medals;
+        }
+        self.medals.update(Some(medals));
+        self.in_bonus_stage.update(Some(in_bonus_stage));
+        self.character_1
+            .update(Some(Character::from_id(snapshot.characters[0])));
+        self.character_2
+            .update(Some(Character::from_id(snapshot.characters[1])));
+        // The stock only exists in Mania Plus, and only in Encore mode
+        self.stock.update(Some(
+            if self
+                .game_mode
+                .pair
+                .is_some_and(|mode| mode.current == GameMode::Encore)
+            {
+                [
+                    Character::from_id(snapshot.stock[0]),
+                    Character::from_id(snapshot.stock[1]),
+                    Character::from_id(snapshot.stock[2]),
+                ]
+            } else {
+                [Character::None; 3]
+            },
+        ));
+
+        self.start_trigger.update(Some(snapshot.start_trigger));
+        self.mania_mode_selection
+            .update(Some(snapshot.mania_mode_selection != 0));
+        self.save_selection_mania
+            .update(Some(snapshot.save_selection_mania));
+        self.save_0.update(Some(snapshot.saves_mania[0]));
+        self.save_1.update(Some(snapshot.saves_mania[1]));
+        self.save_2.update(Some(snapshot.saves_mania[2]));
+        self.save_3.update(Some(snapshot.saves_mania[3]));
+        self.save_4.update(Some(snapshot.saves_mania[4]));
+        self.save_5.update(Some(snapshot.saves_mania[5]));
+        self.save_6.update(Some(snapshot.saves_mania[6]));
+        self.save_7.update(Some(snapshot.saves_mania[7]));
+        self.save_selection_encore
+            .update(Some(snapshot.save_selection_encore));
+        self.save_encore_1
+            .update(Some(snapshot.saves_encore[0] != 0));
+        self.save_encore_2
+            .update(Some(snapshot.saves_encore[1] != 0));
+        self.save_encore_3
+            .update(Some(snapshot.saves_encore[2] != 0));
+        let [state, character, zone] = snapshot.save_progress;
+        self.save_progress.update(Some(SaveProgress {
+            state,
+            character,
+            zone,
+            team: snapshot.save_team.map(Character::from_id),
+        }));
+    }
+}
+
+fn start(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(game_mode) = &watchers.game_mode.pair else {
+        return false;
+    };
+
+    // In Time Attack, the run starts as soon as the countdown is over and the act timer starts running
+    if game_mode.current == GameMode::TimeAttack {
+        let Some(igt) = &watchers.igt.pair else {
+            return false;
+        };
+        return settings.time_attack && igt.old == Duration::ZERO && igt.current != Duration::ZERO;
+    }
+
+    if settings.practice_mode {
+        return practice_start(watchers, settings);
+    }
+
+    if !settings.start_mania_mode && !settings.start_encore_mode {
+        return false;
+    }
+
+    // If you're not in the main menu, there's no reason to continue
+    let Some(levelid) = &watchers.level_id_numeric.pair else {
+        return false;
+    };
+    if levelid.current != 2 {
+        return false;
+    };
+
+    // If the main trigger doesn't get involved, there's no reason to continue
+    let Some(start_trigger) = &watchers.start_trigger.pair else {
+        return false;
+    };
+    if start_trigger.current != start_trigger.old + 48 {
+        return false;
+    };
+
+    let Some(mania_mode_selection) = &watchers.mania_mode_selection.pair else {
+        return false;
+    };
+    if mania_mode_selection.current {
+        if !settings.start_mania_mode {
+            return false;
+        };
+
+        // If, for whatever reason, the save selection is out of bounds, do not continue
+        let Some(save_selection_mania) = &watchers.save_selection_mania.pair else {
+            return false;
+        };
+        if save_selection_mania.current > 8 {
+            return false;
         };
 
         // If the no-save option is selected, there's no need to check the save files
@@ -1015,7 +2463,7 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
             };
 
             // This essentially checks if you selected an empty save file (green hill zone 1). Works for both new games and new game +
-            match save_selection_mania.current {
+            let new_game = match save_selection_mania.current {
                 0 => save_0.current == 255 || save_0.current == 0,
                 1 => save_1.current == 255 || save_1.current == 0,
                 2 => save_2.current == 255 || save_2.current == 0,
@@ -1025,7 +2473,8 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
                 6 => save_6.current == 255 || save_6.current == 0,
                 7 => save_7.current == 255 || save_7.current == 0,
                 _ => false,
-            }
+            };
+            new_game || existing_save_start(watchers, settings, false)
         }
     } else {
         if !settings.start_encore_mode {
@@ -1050,200 +2499,316 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
             let Some(save_3) = &watchers.save_encore_3.pair else {
                 return false;
             };
-            match save_selection_encore.current {
+            let new_game = match save_selection_encore.current {
                 1 => save_1.current,
                 2 => save_2.current,
                 3 => save_3.current,
                 _ => false,
+            };
+            new_game || existing_save_start(watchers, settings, true)
+        }
+    }
+}
+
+/// Whether the last split was a false one, caused by the level ID reaching the next act for a moment
+fn undo_split(watchers: &mut Watchers, settings: &Settings) -> bool {
+    if !settings.undo_false_splits || !watchers.went_back || settings.route == Route::AnyOrder {
+        return false;
+    }
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+
+    // The split happened when leaving the act we are back to, which needs to split again once left for real.
+    // When splitting on act clear, an act that had been cleared already was left without a split.
+    if !settings.split_enabled(level_id.current)
+        || (settings.split_on_act_clear && watchers.left_cleared_act)
+    {
+        return false;
+    }
+    watchers.furthest_act -= 1;
+    true
+}
+
+fn split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(game_mode) = &watchers.game_mode.pair else {
+        return false;
+    };
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+
+    // In Time Attack, we split as soon as the act is cleared, either on the goal post or on the capsule
+    if game_mode.current == GameMode::TimeAttack {
+        let Some(act_clear) = &watchers.act_clear.pair else {
+            return false;
+        };
+        return settings.time_attack && act_clear.current && !act_clear.old;
+    }
+
+    if settings.practice_mode {
+        return practice_split(watchers, settings);
+    }
+
+    // If you're outside Mania or Encore mode, there's no reason to continue
+    if game_mode.current != GameMode::Standard && game_mode.current != GameMode::Encore {
+        return false;
+    };
+
+    // Special Stages, bonus stages, bosses and star posts are not part of the route, so they are checked on their own
+    if special_stage_split(watchers, settings)
+        || bonus_stage_split(watchers, settings)
+        || boss_split(watchers, settings)
+        || star_post_split(watchers, settings)
+    {
+        return true;
+    }
+
+    if act_clear_split(watchers, settings) {
+        return true;
+    }
+
+    if level_id.old == Acts::TitanicMonarch2 || level_id.old == Acts::EncoreTitanicMonarch2 {
+        if game_mode.current == GameMode::Standard {
+            if settings.split_enabled(Acts::TitanicMonarch2) {
+                let Some(chaos_emeralds) = &watchers.chaos_emeralds.pair else {
+                    return false;
+                };
+                let Some(character_1) = &watchers.character_1.pair else {
+                    return false;
+                };
+                let Some(character_2) = &watchers.character_2.pair else {
+                    return false;
+                };
+
+                let true_ending = chaos_emeralds.current == 0x7F
+                    && (character_1.current == Character::Sonic
+                        || (character_1.current == Character::Knuckles
+                            && character_2.current == Character::Knuckles));
+                if settings.route.ends_on_egg_reverie(true_ending) {
+                    return level_id.current == Acts::EggReverie;
+                } else {
+                    let Some(tm2_defeated) = &watchers.titanic_monarch_2_defeated.pair else {
+                        return false;
+                    };
+                    return tm2_defeated.current && !tm2_defeated.old;
+                }
             }
+        } else {
+            let Some(tm2_defeated) = &watchers.titanic_monarch_2_defeated.pair else {
+                return false;
+            };
+            return settings.split_enabled(Acts::EncoreTitanicMonarch2)
+                && tm2_defeated.current
+                && !tm2_defeated.old;
         }
     }
+    // Egg Reverie needs a split when the bosses are defeated
+    else if level_id.old == Acts::EggReverie {
+        if settings.split_enabled(Acts::EggReverie) {
+            let Some(status) = &watchers.status.pair else {
+                return false;
+            };
+            let Some(er_health) = &watchers.egg_reverie_health.pair else {
+                return false;
+            };
+            return status.current == 1 && er_health.old > 0 && er_health.current == 0;
+        }
+    } else {
+        let Some(act_cleared) = &watchers.act_cleared.pair else {
+            return false;
+        };
+
+        // When splitting on act clear, loading the next act is only a fallback in case the results screen was missed
+        return settings.split_enabled(level_id.old)
+            && settings.route.expects(level_id.old, level_id.current)
+            && match settings.route {
+                Route::AnyOrder => watchers.new_act,
+                _ => watchers.progressed,
+            }
+            && !(settings.split_on_act_clear && act_cleared.old);
+    }
+    false
+}
+
+fn special_stage_split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(chaos_emeralds) = &watchers.chaos_emeralds.pair else {
+        return false;
+    };
+    let Some(in_special_stage) = &watchers.in_special_stage.pair else {
+        return false;
+    };
+
+    // A new bit in the bitmask is a new emerald. Emeralds are only checked while in the Special Stage,
+    // as loading a save file also changes the bitmask.
+    let collected = chaos_emeralds.current & !chaos_emeralds.old;
+    if collected != 0 && (in_special_stage.current || in_special_stage.old) {
+        return settings.chaos_emerald_split_enabled(collected.trailing_zeros());
+    }
+
+    settings.special_stage_failed
+        && in_special_stage.old
+        && !in_special_stage.current
+        && chaos_emeralds.current == watchers.emeralds_on_entry
+}
+
+fn bonus_stage_split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(medals) = &watchers.medals.pair else {
+        return false;
+    };
+    let Some(in_bonus_stage) = &watchers.in_bonus_stage.pair else {
+        return false;
+    };
+
+    if medals.current.score() > medals.old.score() && (in_bonus_stage.current || in_bonus_stage.old)
+    {
+        return settings.medal_earned;
+    }
+
+    if in_bonus_stage.old && !in_bonus_stage.current {
+        return settings.bonus_stage_exited
+            || (settings.bonus_stage_failed && medals.current == watchers.medals_on_entry);
+    }
+
+    false
+}
+
+/// Runs can also start from a save with some progress already, as long as it matches the starting point
+/// chosen in the settings. Empty saves are handled as new games.
+fn existing_save_start(watchers: &Watchers, settings: &Settings, encore: bool) -> bool {
+    if !settings.start_from_save {
+        return false;
+    }
+    let Some(save_progress) = &watchers.save_progress.pair else {
+        return false;
+    };
+    let save = save_progress.current;
+
+    save.state != 0
+        && save.zone == settings.start_save_zone as u8
+        // Encore mode saves store the whole team instead of a single character
+        && if encore {
+            settings.start_save_character.matches_team(save.team)
+        } else {
+            settings.start_save_character.matches(save.character)
+        }
+}
+
+/// In practice mode, the run starts as soon as the act timer starts running from zero in the chosen act
+fn practice_start(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+    let Some(in_act) = &watchers.in_act.pair else {
+        return false;
+    };
+    let Some(igt) = &watchers.igt.pair else {
+        return false;
+    };
+
+    in_act.current
+        && level_id.current == settings.practice_act
+        && igt.old == Duration::ZERO
+        && igt.current != Duration::ZERO
 }
 
-fn split(watchers: &Watchers, settings: &Settings) -> bool {
-    let Some(game_mode) = &watchers.game_mode.pair else {
+fn practice_split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+    let Some(act_cleared) = &watchers.act_cleared.pair else {
         return false;
     };
+
+    level_id.current == settings.practice_act && act_cleared.current && !act_cleared.old
+}
+
+/// Restarting the act (or dying before any star post) brings the act timer back to zero.
+/// Once the act is cleared, the timer going back to zero only means the next act is loading.
+fn practice_reset(watchers: &Watchers, settings: &Settings) -> bool {
     let Some(level_id) = &watchers.level_id.pair else {
         return false;
     };
+    let Some(act_cleared) = &watchers.act_cleared.pair else {
+        return false;
+    };
+    let Some(igt) = &watchers.igt.pair else {
+        return false;
+    };
 
-    // If you're outside Mania or Encore mode, there's no reason to continue
-    if game_mode.current != GameMode::Standard && game_mode.current != GameMode::Encore {
+    level_id.current == settings.practice_act
+        && !act_cleared.old
+        && !act_cleared.current
+        && igt.old != Duration::ZERO
+        && igt.current == Duration::ZERO
+}
+
+fn act_clear_split(watchers: &Watchers, settings: &Settings) -> bool {
+    if !settings.split_on_act_clear {
+        return false;
+    }
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+    let Some(act_cleared) = &watchers.act_cleared.pair else {
         return false;
     };
 
-    if level_id.old == Acts::TitanicMonarch2 || level_id.old == Acts::EncoreTitanicMonarch2 {
-        if game_mode.current == GameMode::Standard {
-            if settings.titanic_monarch_2 {
-                let Some(chaos_emeralds) = &watchers.chaos_emeralds.pair else {
-                    return false;
-                };
-                let Some(character_1) = &watchers.character_1.pair else {
-                    return false;
-                };
-                let Some(character_2) = &watchers.character_2.pair else {
-                    return false;
-                };
+    // The last acts of the game already split when their final boss is defeated, and so do the other
+    // Act 2s when their boss split is enabled, as defeating the boss is what clears them
+    if matches!(
+        level_id.current,
+        Acts::TitanicMonarch2 | Acts::EncoreTitanicMonarch2 | Acts::EggReverie
+    ) || Boss::from_act(level_id.current).is_some_and(|boss| settings.boss_split_enabled(boss))
+    {
+        return false;
+    }
 
-                if chaos_emeralds.current == 0x7F
-                    && (character_1.current == 1
-                        || (character_1.current == 4 && character_2.current == 4))
-                {
-                    return level_id.current == Acts::EggReverie;
-                } else {
-                    let Some(tm2_defeated) = &watchers.titanic_monarch_2_defeated.pair else {
-                        return false;
-                    };
-                    return tm2_defeated.current && !tm2_defeated.old;
-                }
-            }
-        } else {
-            let Some(tm2_defeated) = &watchers.titanic_monarch_2_defeated.pair else {
-                return false;
-            };
-            return settings.encore_titanic_monarch_2 && tm2_defeated.current && !tm2_defeated.old;
+    // Clearing an act we've already gone past (eg. after a level select warp), or already played on the any
+    // order route, is not progress
+    act_cleared.current
+        && !act_cleared.old
+        && settings.split_enabled(level_id.current)
+        && settings.route.contains(level_id.current)
+        && match settings.route {
+            Route::AnyOrder => !watchers.replaying_act,
+            _ => route::position(level_id.current)
+                .is_some_and(|position| position >= watchers.furthest_act),
         }
-    }
-    // Egg Reverie needs a split when the bosses are defeated
-    else if level_id.old == Acts::EggReverie {
-        if settings.egg_reverie {
-            let Some(status) = &watchers.status.pair else {
-                return false;
-            };
-            let Some(er_health) = &watchers.egg_reverie_health.pair else {
-                return false;
-            };
-            return status.current == 1 && er_health.old > 0 && er_health.current == 0;
+}
+
+fn boss_split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(boss) = &watchers.boss.pair else {
+        return false;
+    };
+
+    match (boss.old, boss.current) {
+        (BossState::Fighting(fought), BossState::Defeated(defeated)) if fought == defeated => {
+            settings.boss_split_enabled(defeated)
         }
-    } else {
-        return match level_id.old {
-            Acts::GreenHill1 => settings.green_hill_1 && level_id.current == Acts::GreenHill2,
-            Acts::GreenHill2 => settings.green_hill_2 && level_id.current == Acts::ChemicalPlant1,
-            Acts::ChemicalPlant1 => {
-                settings.chemical_plant_1 && level_id.current == Acts::ChemicalPlant2
-            }
-            Acts::ChemicalPlant2 => {
-                settings.chemical_plant_2 && level_id.current == Acts::Studiopolis1
-            }
-            Acts::Studiopolis1 => settings.studiopolis_1 && level_id.current == Acts::Studiopolis2,
-            Acts::Studiopolis2 => {
-                settings.studiopolis_2 && level_id.current == Acts::FlyingBattery1
-            }
-            Acts::FlyingBattery1 => {
-                settings.flying_battery_1 && level_id.current == Acts::FlyingBattery2
-            }
-            Acts::FlyingBattery2 => {
-                settings.flying_battery_2 && level_id.current == Acts::PressGarden1
-            }
-            Acts::PressGarden1 => settings.press_garden_1 && level_id.current == Acts::PressGarden2,
-            Acts::PressGarden2 => {
-                settings.press_garden_2 && level_id.current == Acts::StardustSpeedway1
-            }
-            Acts::StardustSpeedway1 => {
-                settings.stardust_speedway_1 && level_id.current == Acts::StardustSpeedway2
-            }
-            Acts::StardustSpeedway2 => {
-                settings.stardust_speedway_2 && level_id.current == Acts::Hydrocity1
-            }
-            Acts::Hydrocity1 => settings.hydrocity_1 && level_id.current == Acts::Hydrocity2,
-            Acts::Hydrocity2 => settings.hydrocity_2 && level_id.current == Acts::MirageSaloon1,
-            Acts::MirageSaloon1 => {
-                settings.mirage_saloon_1 && level_id.current == Acts::MirageSaloon2
-            }
-            Acts::MirageSaloon2 => settings.mirage_saloon_2 && level_id.current == Acts::OilOcean1,
-            Acts::OilOcean1 => settings.oil_ocean_1 && level_id.current == Acts::OilOcean2,
-            Acts::OilOcean2 => settings.oil_ocean_2 && level_id.current == Acts::LavaReef1,
-            Acts::LavaReef1 => settings.lava_reef_1 && level_id.current == Acts::LavaReef2,
-            Acts::LavaReef2 => settings.lava_reef_2 && level_id.current == Acts::MetallicMadness1,
-            Acts::MetallicMadness1 => {
-                settings.metallic_madness_1 && level_id.current == Acts::MetallicMadness2
-            }
-            Acts::MetallicMadness2 => {
-                settings.metallic_madness_2 && level_id.current == Acts::TitanicMonarch1
-            }
-            Acts::TitanicMonarch1 => {
-                settings.titanic_monarch_1 && level_id.current == Acts::TitanicMonarch2
-            }
-            Acts::EncoreAngelIsland => {
-                settings.angel_island && level_id.current == Acts::EncoreGreenHill1
-            }
-            Acts::EncoreGreenHill1 => {
-                settings.encore_green_hill_1 && level_id.current == Acts::EncoreGreenHill2
-            }
-            Acts::EncoreGreenHill2 => {
-                settings.encore_green_hill_2 && level_id.current == Acts::EncoreChemicalPlant1
-            }
-            Acts::EncoreChemicalPlant1 => {
-                settings.encore_chemical_plant_1 && level_id.current == Acts::EncoreChemicalPlant2
-            }
-            Acts::EncoreChemicalPlant2 => {
-                settings.encore_chemical_plant_2 && level_id.current == Acts::EncoreStudiopolis1
-            }
-            Acts::EncoreStudiopolis1 => {
-                settings.encore_studiopolis_1 && level_id.current == Acts::EncoreStudiopolis2
-            }
-            Acts::EncoreStudiopolis2 => {
-                settings.encore_studiopolis_2 && level_id.current == Acts::EncoreFlyingBattery1
-            }
-            Acts::EncoreFlyingBattery1 => {
-                settings.encore_flying_battery_1 && level_id.current == Acts::EncoreFlyingBattery2
-            }
-            Acts::EncoreFlyingBattery2 => {
-                settings.encore_flying_battery_2 && level_id.current == Acts::EncorePressGarden1
-            }
-            Acts::EncorePressGarden1 => {
-                settings.encore_press_garden_1 && level_id.current == Acts::EncorePressGarden2
-            }
-            Acts::EncorePressGarden2 => {
-                settings.encore_press_garden_2 && level_id.current == Acts::EncoreStardustSpeedway1
-            }
-            Acts::EncoreStardustSpeedway1 => {
-                settings.encore_stardust_speedway_1
-                    && level_id.current == Acts::EncoreStardustSpeedway2
-            }
-            Acts::EncoreStardustSpeedway2 => {
-                settings.encore_stardust_speedway_2 && level_id.current == Acts::EncoreHydrocity1
-            }
-            Acts::EncoreHydrocity1 => {
-                settings.encore_hydrocity_1 && level_id.current == Acts::EncoreHydrocity2
-            }
-            Acts::EncoreHydrocity2 => {
-                settings.encore_hydrocity_2 && level_id.current == Acts::EncoreMirageSaloon1
-            }
-            Acts::EncoreMirageSaloon1 => {
-                settings.encore_mirage_saloon_1 && level_id.current == Acts::EncoreMirageSaloon2
-            }
-            Acts::EncoreMirageSaloon2 => {
-                settings.encore_mirage_saloon_2 && level_id.current == Acts::EncoreOilOcean1
-            }
-            Acts::EncoreOilOcean1 => {
-                settings.encore_oil_ocean_1 && level_id.current == Acts::EncoreOilOcean2
-            }
-            Acts::EncoreOilOcean2 => {
-                settings.encore_oil_ocean_2 && level_id.current == Acts::EncoreLavaReef1
-            }
-            Acts::EncoreLavaReef1 => {
-                settings.encore_lava_reef_1 && level_id.current == Acts::EncoreLavaReef2
-            }
-            Acts::EncoreLavaReef2 => {
-                settings.encore_lava_reef_2 && level_id.current == Acts::EncoreMetallicMadness1
-            }
-            Acts::EncoreMetallicMadness1 => {
-                settings.encore_metallic_madness_1
-                    && level_id.current == Acts::EncoreMetallicMadness2
-            }
-            Acts::EncoreMetallicMadness2 => {
-                settings.encore_metallic_madness_2
-                    && level_id.current == Acts::EncoreTitanicMonarch1
-            }
-            Acts::EncoreTitanicMonarch1 => {
-                settings.encore_titanic_monarch_1 && level_id.current == Acts::EncoreTitanicMonarch2
-            }
-            _ => false,
-        };
+        _ => false,
     }
-    false
+}
+
+fn star_post_split(watchers: &Watchers, settings: &Settings) -> bool {
+    let Some(star_posts_reached) = &watchers.star_posts_reached.pair else {
+        return false;
+    };
+    let Some(level_id) = &watchers.level_id.pair else {
+        return false;
+    };
+    let Some(in_act) = &watchers.in_act.pair else {
+        return false;
+    };
+
+    // Every star post is a separate entity, so touching a new one changes the slot we respawn from.
+    // Respawning doesn't, while loading a new act clears it.
+    in_act.current
+        && !level_id.changed()
+        && star_posts_reached.current > star_posts_reached.old
+        && settings
+            .star_posts(level_id.current)
+            .splits_on(star_posts_reached.current)
 }
 
 fn reset(watchers: &Watchers, settings: &Settings) -> bool {
@@ -1253,6 +2818,28 @@ fn reset(watchers: &Watchers, settings: &Settings) -> bool {
     let Some(levelid_numeric) = &watchers.level_id_numeric.pair else {
         return false;
     };
+    let Some(game_mode) = &watchers.game_mode.pair else {
+        return false;
+    };
+
+    // In Time Attack, restarting the act brings the timer back to zero, while exiting brings us outside the act
+    if settings.time_attack && game_mode.current == GameMode::TimeAttack {
+        let Some(igt) = &watchers.igt.pair else {
+            return false;
+        };
+        let Some(in_act) = &watchers.in_act.pair else {
+            return false;
+        };
+        if (igt.old != Duration::ZERO && igt.current == Duration::ZERO)
+            || (in_act.old && !in_act.current)
+        {
+            return true;
+        }
+    }
+
+    if settings.practice_mode && practice_reset(watchers, settings) {
+        return true;
+    }
 
     (settings.reset_save_select
         && levelid_numeric.old != 1
@@ -1261,63 +2848,608 @@ fn reset(watchers: &Watchers, settings: &Settings) -> bool {
         || (settings.reset_dev_menu && status.changed() && status.current == 8)
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Gui, Clone, Copy, PartialEq)]
 enum Acts {
+    /// Green Hill Act 1
+    #[default]
     GreenHill1,
+    /// Green Hill Act 2
     GreenHill2,
+    /// Chemical Plant Act 1
     ChemicalPlant1,
+    /// Chemical Plant Act 2
     ChemicalPlant2,
+    /// Studiopolis Act 1
     Studiopolis1,
+    /// Studiopolis Act 2
     Studiopolis2,
+    /// Flying Battery Act 1
     FlyingBattery1,
+    /// Flying Battery Act 2
     FlyingBattery2,
+    /// Press Garden Act 1
     PressGarden1,
+    /// Press Garden Act 2
     PressGarden2,
+    /// Stardust Speedway Act 1
     StardustSpeedway1,
+    /// Stardust Speedway Act 2
     StardustSpeedway2,
+    /// Hydrocity Act 1
     Hydrocity1,
+    /// Hydrocity Act 2
     Hydrocity2,
+    /// Mirage Saloon Act 1
     MirageSaloon1,
+    /// Mirage Saloon Act 2
     MirageSaloon2,
+    /// Oil Ocean Act 1
     OilOcean1,
+    /// Oil Ocean Act 2
     OilOcean2,
+    /// Lava Reef Act 1
     LavaReef1,
+    /// Lava Reef Act 2
     LavaReef2,
+    /// Metallic Madness Act 1
     MetallicMadness1,
+    /// Metallic Madness Act 2
     MetallicMadness2,
+    /// Titanic Monarch Act 1
     TitanicMonarch1,
+    /// Titanic Monarch Act 2
     TitanicMonarch2,
+    /// Egg Reverie
     EggReverie,
+    /// Angel Island (Encore)
     EncoreAngelIsland,
+    /// Green Hill Act 1 (Encore)
     EncoreGreenHill1,
+    /// Green Hill Act 2 (Encore)
     EncoreGreenHill2,
+    /// Chemical Plant Act 1 (Encore)
     EncoreChemicalPlant1,
+    /// Chemical Plant Act 2 (Encore)
     EncoreChemicalPlant2,
+    /// Studiopolis Act 1 (Encore)
     EncoreStudiopolis1,
+    /// Studiopolis Act 2 (Encore)
     EncoreStudiopolis2,
+    /// Flying Battery Act 1 (Encore)
     EncoreFlyingBattery1,
+    /// Flying Battery Act 2 (Encore)
     EncoreFlyingBattery2,
+    /// Press Garden Act 1 (Encore)
     EncorePressGarden1,
+    /// Press Garden Act 2 (Encore)
     EncorePressGarden2,
+    /// Stardust Speedway Act 1 (Encore)
     EncoreStardustSpeedway1,
+    /// Stardust Speedway Act 2 (Encore)
     EncoreStardustSpeedway2,
+    /// Hydrocity Act 1 (Encore)
     EncoreHydrocity1,
+    /// Hydrocity Act 2 (Encore)
     EncoreHydrocity2,
+    /// Mirage Saloon Act 1 (Encore)
     EncoreMirageSaloon1,
+    /// Mirage Saloon Act 2 (Encore)
     EncoreMirageSaloon2,
+    /// Oil Ocean Act 1 (Encore)
     EncoreOilOcean1,
+    /// Oil Ocean Act 2 (Encore)
     EncoreOilOcean2,
+    /// Lava Reef Act 1 (Encore)
     EncoreLavaReef1,
+    /// Lava Reef Act 2 (Encore)
     EncoreLavaReef2,
+    /// Metallic Madness Act 1 (Encore)
     EncoreMetallicMadness1,
+    /// Metallic Madness Act 2 (Encore)
     EncoreMetallicMadness2,
+    /// Titanic Monarch Act 1 (Encore)
     EncoreTitanicMonarch1,
+    /// Titanic Monarch Act 2 (Encore)
     EncoreTitanicMonarch2,
 }
 
+impl Acts {
+    /// Maps the level ID reported by the game to the act it belongs to.
+    /// Returns `None` if the level ID does not refer to any act (eg. menus, special stages, etc.)
+    fn from_level_id(game_mode: u8, level_id: u8) -> Option<Self> {
+        match game_mode {
+            0 => Self::from_mania_level_id(level_id),
+            // Time Attack can be played on the stages from both Mania and Encore mode
+            2 => {
+                Self::from_mania_level_id(level_id).or_else(|| Self::from_encore_level_id(level_id))
+            }
+            _ => Self::from_encore_level_id(level_id),
+        }
+    }
+
+    fn from_mania_level_id(level_id: u8) -> Option<Self> {
+        Some(match level_id {
+            9 | 117 | 119 => Acts::GreenHill1,
+            10 | 120 => Acts::GreenHill2,
+            11 => Acts::ChemicalPlant1,
+            12 => Acts::ChemicalPlant2,
+            13 => Acts::Studiopolis1,
+            14 => Acts::Studiopolis2,
+            15 => Acts::FlyingBattery1,
+            16 => Acts::FlyingBattery2,
+            17 => Acts::PressGarden1,
+            18 => Acts::PressGarden2,
+            19 | 122 => Acts::StardustSpeedway1,
+            20 | 21 => Acts::StardustSpeedway2,
+            22 => Acts::Hydrocity1,
+            23 => Acts::Hydrocity2,
+            24 | 25 => Acts::MirageSaloon1,
+            26 => Acts::MirageSaloon2,
+            27 => Acts::OilOcean1,
+            28 => Acts::OilOcean2,
+            29 => Acts::LavaReef1,
+            30 | 31 => Acts::LavaReef2,
+            32 => Acts::MetallicMadness1,
+            33 => Acts::MetallicMadness2,
+            34 => Acts::TitanicMonarch1,
+            35 | 36 => Acts::TitanicMonarch2,
+            37 => Acts::EggReverie,
+            _ => return None,
+        })
+    }
+
+    fn from_encore_level_id(level_id: u8) -> Option<Self> {
+        Some(match level_id {
+            118 => Acts::EncoreAngelIsland,
+            119 | 38 => Acts::EncoreGreenHill1,
+            39 | 120 => Acts::EncoreGreenHill2,
+            40 => Acts::EncoreChemicalPlant1,
+            41 => Acts::EncoreChemicalPlant2,
+            42 => Acts::EncoreStudiopolis1,
+            43 => Acts::EncoreStudiopolis2,
+            44 => Acts::EncoreFlyingBattery1,
+            45 => Acts::EncoreFlyingBattery2,
+            46 => Acts::EncorePressGarden1,
+            47 => Acts::EncorePressGarden2,
+            48 | 122 => Acts::EncoreStardustSpeedway1,
+            49 | 50 => Acts::EncoreStardustSpeedway2,
+            51 => Acts::EncoreHydrocity1,
+            52 => Acts::EncoreHydrocity2,
+            53 => Acts::EncoreMirageSaloon1,
+            54 => Acts::EncoreMirageSaloon2,
+            55 => Acts::EncoreOilOcean1,
+            56 => Acts::EncoreOilOcean2,
+            57 => Acts::EncoreLavaReef1,
+            58 | 59 => Acts::EncoreLavaReef2,
+            60 => Acts::EncoreMetallicMadness1,
+            61 => Acts::EncoreMetallicMadness2,
+            62 => Acts::EncoreTitanicMonarch1,
+            63 | 64 => Acts::EncoreTitanicMonarch2,
+            _ => return None,
+        })
+    }
+
+    /// Human-readable name of the act, eg. "Studiopolis Act 2 (Encore)"
+    fn name(self) -> ArrayString<48> {
+        let (zone, act) = match self {
+            Self::GreenHill1 | Self::EncoreGreenHill1 => ("Green Hill", Some(1)),
+            Self::GreenHill2 | Self::EncoreGreenHill2 => ("Green Hill", Some(2)),
+            Self::ChemicalPlant1 | Self::EncoreChemicalPlant1 => ("Chemical Plant", Some(1)),
+            Self::ChemicalPlant2 | Self::EncoreChemicalPlant2 => ("Chemical Plant", Some(2)),
+            Self::Studiopolis1 | Self::EncoreStudiopolis1 => ("Studiopolis", Some(1)),
+            Self::Studiopolis2 | Self::EncoreStudiopolis2 => ("Studiopolis", Some(2)),
+            Self::FlyingBattery1 | Self::EncoreFlyingBattery1 => ("Flying Battery", Some(1)),
+            Self::FlyingBattery2 | Self::EncoreFlyingBattery2 => ("Flying Battery", Some(2)),
+            Self::PressGarden1 | Self::EncorePressGarden1 => ("Press Garden", Some(1)),
+            Self::PressGarden2 | Self::EncorePressGarden2 => ("Press Garden", Some(2)),
+            Self::StardustSpeedway1 | Self::EncoreStardustSpeedway1 => {
+                ("Stardust Speedway", Some(1))
+            }
+            Self::StardustSpeedway2 | Self::EncoreStardustSpeedway2 => {
+                ("Stardust Speedway", Some(2))
+            }
+            Self::Hydrocity1 | Self::EncoreHydrocity1 => ("Hydrocity", Some(1)),
+            Self::Hydrocity2 | Self::EncoreHydrocity2 => ("Hydrocity", Some(2)),
+            Self::MirageSaloon1 | Self::EncoreMirageSaloon1 => ("Mirage Saloon", Some(1)),
+            Self::MirageSaloon2 | Self::EncoreMirageSaloon2 => ("Mirage Saloon", Some(2)),
+            Self::OilOcean1 | Self::EncoreOilOcean1 => ("Oil Ocean", Some(1)),
+            Self::OilOcean2 | Self::EncoreOilOcean2 => ("Oil Ocean", Some(2)),
+            Self::LavaReef1 | Self::EncoreLavaReef1 => ("Lava Reef", Some(1)),
+            Self::LavaReef2 | Self::EncoreLavaReef2 => ("Lava Reef", Some(2)),
+            Self::MetallicMadness1 | Self::EncoreMetallicMadness1 => ("Metallic Madness", Some(1)),
+            Self::MetallicMadness2 | Self::EncoreMetallicMadness2 => ("Metallic Madness", Some(2)),
+            Self::TitanicMonarch1 | Self::EncoreTitanicMonarch1 => ("Titanic Monarch", Some(1)),
+            Self::TitanicMonarch2 | Self::EncoreTitanicMonarch2 => ("Titanic Monarch", Some(2)),
+            Self::EggReverie => ("Egg Reverie", None),
+            // Encore mode's prologue, played as a single act
+            Self::EncoreAngelIsland => ("Angel Island", None),
+        };
+
+        let mut name = ArrayString::new();
+        name.push_str(zone);
+        if let Some(act) = act {
+            let _ = write!(name, " Act {act}");
+        }
+        if self.is_encore() {
+            name.push_str(" (Encore)");
+        }
+        name
+    }
+
+    /// Encore mode's acts are all listed after the ones from Mania mode
+    fn is_encore(self) -> bool {
+        self as u8 >= Self::EncoreAngelIsland as u8
+    }
+}
+
+/// Bosses fought at the end of every zone, both in Mania and Encore mode
+#[derive(Clone, Copy, PartialEq)]
+enum Boss {
+    GreenHill,
+    ChemicalPlant,
+    Studiopolis,
+    FlyingBattery,
+    PressGarden,
+    StardustSpeedway,
+    Hydrocity,
+    MirageSaloon,
+    OilOcean,
+    LavaReef,
+    MetallicMadness,
+    TitanicMonarch,
+}
+
+impl Boss {
+    fn from_act(act: Acts) -> Option<Self> {
+        Some(match act {
+            Acts::GreenHill2 | Acts::EncoreGreenHill2 => Self::GreenHill,
+            Acts::ChemicalPlant2 | Acts::EncoreChemicalPlant2 => Self::ChemicalPlant,
+            Acts::Studiopolis2 | Acts::EncoreStudiopolis2 => Self::Studiopolis,
+            Acts::FlyingBattery2 | Acts::EncoreFlyingBattery2 => Self::FlyingBattery,
+            Acts::PressGarden2 | Acts::EncorePressGarden2 => Self::PressGarden,
+            Acts::StardustSpeedway2 | Acts::EncoreStardustSpeedway2 => Self::StardustSpeedway,
+            Acts::Hydrocity2 | Acts::EncoreHydrocity2 => Self::Hydrocity,
+            Acts::MirageSaloon2 | Acts::EncoreMirageSaloon2 => Self::MirageSaloon,
+            Acts::OilOcean2 | Acts::EncoreOilOcean2 => Self::OilOcean,
+            Acts::LavaReef2 | Acts::EncoreLavaReef2 => Self::LavaReef,
+            Acts::MetallicMadness2 | Acts::EncoreMetallicMadness2 => Self::MetallicMadness,
+            Acts::TitanicMonarch2 | Acts::EncoreTitanicMonarch2 => Self::TitanicMonarch,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::GreenHill => "Death Egg Robot",
+            Self::ChemicalPlant => "Mean Bean Machine",
+            Self::Studiopolis => "Weather Mobile",
+            Self::FlyingBattery => "Big Squeeze",
+            Self::PressGarden => "Heavy Shinobi",
+            Self::StardustSpeedway => "Metal Sonic",
+            Self::Hydrocity => "Laundro Mobile",
+            Self::MirageSaloon => "Heavy Magician",
+            Self::OilOcean => "Mega Octus",
+            Self::LavaReef => "Heavy Rider",
+            Self::MetallicMadness => "Egg Pistons Mk. II",
+            Self::TitanicMonarch => "Phantom Egg",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BossState {
+    None,
+    Fighting(Boss),
+    Defeated(Boss),
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum GameMode {
     Standard,
     Encore,
+    TimeAttack,
     Invalid,
 }
+
+#[derive(Clone, Copy, PartialEq, Default)]
+enum LoadState {
+    #[default]
+    Playing,
+    Loading,
+    TitleCard,
+    ActClear,
+    Menu,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+enum TimingMethod {
+    /// In-game time (IGT)
+    #[default]
+    InGameTime,
+    /// Load removed time (LRT)
+    LoadRemovedTime,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Character {
+    #[default]
+    None,
+    Sonic,
+    Tails,
+    Knuckles,
+    Mighty,
+    Ray,
+}
+
+impl Character {
+    /// The game identifies every character with a separate bit
+    fn from_id(id: u8) -> Self {
+        match id {
+            1 => Self::Sonic,
+            2 => Self::Tails,
+            4 => Self::Knuckles,
+            8 => Self::Mighty,
+            16 => Self::Ray,
+            _ => Self::None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Sonic => "Sonic",
+            Self::Tails => "Tails",
+            Self::Knuckles => "Knuckles",
+            Self::Mighty => "Mighty",
+            Self::Ray => "Ray",
+        }
+    }
+}
+
+/// Build of the game we are attached to
+#[derive(Clone, Copy, PartialEq)]
+struct GameVariant {
+    build: Build,
+    is_64_bit: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Build {
+    Steam,
+    Epic,
+    Origin,
+    RsdkV5,
+    RsdkV5U,
+    /// Retail build we couldn't tell the store of
+    Unknown,
+}
+
+impl GameVariant {
+    fn detect(
+        process: &Process,
+        modules: &Modules,
+        main_module_name: &str,
+        is_64_bit: bool,
+    ) -> Self {
+        let build = if signatures::is_rsdk_5u(process, modules.main, is_64_bit) {
+            Build::RsdkV5U
+        } else if modules.game != modules.main || main_module_name != "SonicMania.exe" {
+            Build::RsdkV5
+        } else if process.get_module_address("steam_api.dll").is_ok() {
+            Build::Steam
+        } else if process
+            .get_module_address("EOSSDK-Win32-Shipping.dll")
+            .is_ok()
+        {
+            Build::Epic
+        } else if process.get_module_address("Activation.dll").is_ok() {
+            // Loaded by Origin's DRM
+            Build::Origin
+        } else {
+            // Read with the layout of the other retail builds, which is the closest guess we have
+            runtime::print_message("Unrecognised retail build of the game");
+            Build::Unknown
+        };
+
+        Self { build, is_64_bit }
+    }
+
+    fn name(self) -> ArrayString<48> {
+        let mut name = ArrayString::new();
+        name.push_str(match self.build {
+            Build::Steam => "Retail (Steam)",
+            Build::Epic => "Retail (Epic Games Store)",
+            Build::Origin => "Retail (Origin)",
+            Build::RsdkV5 => "RSDKv5 decomp",
+            Build::RsdkV5U => "RSDKv5U decomp",
+            Build::Unknown => "Unknown",
+        });
+        name.push_str(if self.is_64_bit {
+            ", 64-bit"
+        } else {
+            ", 32-bit"
+        });
+        name
+    }
+}
+
+/// Progress stored in the save slot selected in the menu
+#[derive(Clone, Copy, Default, PartialEq)]
+struct SaveProgress {
+    state: u8,
+    character: u8,
+    zone: u8,
+    /// Leader and buddy, only stored in Encore mode
+    team: [Character; 2],
+}
+
+/// Zones as numbered in the save files
+#[derive(Gui, Clone, Copy, PartialEq)]
+enum Zone {
+    /// Green Hill
+    #[default]
+    GreenHill,
+    /// Chemical Plant
+    ChemicalPlant,
+    /// Studiopolis
+    Studiopolis,
+    /// Flying Battery
+    FlyingBattery,
+    /// Press Garden
+    PressGarden,
+    /// Stardust Speedway
+    StardustSpeedway,
+    /// Hydrocity
+    Hydrocity,
+    /// Mirage Saloon
+    MirageSaloon,
+    /// Oil Ocean
+    OilOcean,
+    /// Lava Reef
+    LavaReef,
+    /// Metallic Madness
+    MetallicMadness,
+    /// Titanic Monarch
+    TitanicMonarch,
+}
+
+/// Characters as numbered in the Mania mode save files
+#[derive(Gui, Clone, Copy, PartialEq)]
+enum SaveCharacter {
+    /// Any character
+    #[default]
+    Any,
+    /// Sonic & Tails
+    SonicAndTails,
+    /// Sonic
+    Sonic,
+    /// Tails
+    Tails,
+    /// Knuckles
+    Knuckles,
+    /// Mighty
+    Mighty,
+    /// Ray
+    Ray,
+}
+
+impl SaveCharacter {
+    fn matches(self, id: u8) -> bool {
+        match self {
+            Self::Any => true,
+            // Sonic & Tails is stored as 0, and so on
+            _ => self as u8 - 1 == id,
+        }
+    }
+
+    /// Encore mode saves are told apart by their leader, and Sonic & Tails by the buddy as well
+    fn matches_team(self, [leader, buddy]: [Character; 2]) -> bool {
+        match self {
+            Self::Any => true,
+            Self::SonicAndTails => leader == Character::Sonic && buddy == Character::Tails,
+            Self::Sonic => leader == Character::Sonic,
+            Self::Tails => leader == Character::Tails,
+            Self::Knuckles => leader == Character::Knuckles,
+            Self::Mighty => leader == Character::Mighty,
+            Self::Ray => leader == Character::Ray,
+        }
+    }
+}
+
+/// Star posts to split on in an act, numbered in the order they are reached
+#[derive(Gui, Clone, Copy, PartialEq)]
+enum StarPosts {
+    /// None
+    #[default]
+    None,
+    /// Every star post
+    Every,
+    /// 1st star post
+    First,
+    /// 2nd star post
+    Second,
+    /// 3rd star post
+    Third,
+    /// 4th star post
+    Fourth,
+    /// 5th star post
+    Fifth,
+    /// 6th star post
+    Sixth,
+    /// 7th star post
+    Seventh,
+    /// 8th star post
+    Eighth,
+}
+
+impl StarPosts {
+    fn splits_on(self, number: u8) -> bool {
+        match self {
+            Self::None => false,
+            Self::Every => true,
+            // The 1st star post comes right after Every, and so on
+            _ => self as u8 - 1 == number,
+        }
+    }
+}
+
+/// Declares the choice of the number of segments, labelled with the number itself
+macro_rules! segments {
+    ($($variant:ident = $label:literal,)*) => {
+        /// Number of segments in the splits file
+        #[derive(Gui, Clone, Copy, PartialEq)]
+        enum Segments {
+            /// Don't check
+            #[default]
+            Unchecked,
+            $(#[doc = $label] $variant,)*
+        }
+    };
+}
+
+segments! {
+    S1 = "1", S2 = "2", S3 = "3", S4 = "4", S5 = "5", S6 = "6", S7 = "7", S8 = "8",
+    S9 = "9", S10 = "10", S11 = "11", S12 = "12", S13 = "13", S14 = "14", S15 = "15", S16 = "16",
+    S17 = "17", S18 = "18", S19 = "19", S20 = "20", S21 = "21", S22 = "22", S23 = "23", S24 = "24",
+    S25 = "25", S26 = "26", S27 = "27", S28 = "28", S29 = "29", S30 = "30", S31 = "31", S32 = "32",
+    S33 = "33", S34 = "34", S35 = "35", S36 = "36", S37 = "37", S38 = "38", S39 = "39", S40 = "40",
+    S41 = "41", S42 = "42", S43 = "43", S44 = "44", S45 = "45", S46 = "46", S47 = "47", S48 = "48",
+    S49 = "49", S50 = "50", S51 = "51", S52 = "52", S53 = "53", S54 = "54", S55 = "55", S56 = "56",
+    S57 = "57", S58 = "58", S59 = "59", S60 = "60", S61 = "61", S62 = "62", S63 = "63", S64 = "64",
+    S65 = "65", S66 = "66", S67 = "67", S68 = "68", S69 = "69", S70 = "70", S71 = "71", S72 = "72",
+    S73 = "73", S74 = "74", S75 = "75", S76 = "76", S77 = "77", S78 = "78", S79 = "79", S80 = "80",
+    S81 = "81", S82 = "82", S83 = "83", S84 = "84", S85 = "85", S86 = "86", S87 = "87", S88 = "88",
+    S89 = "89", S90 = "90", S91 = "91", S92 = "92", S93 = "93", S94 = "94", S95 = "95", S96 = "96",
+    S97 = "97", S98 = "98", S99 = "99",
+}
+
+impl Segments {
+    fn count(self) -> Option<u8> {
+        match self {
+            Self::Unchecked => None,
+            // Every count comes right after the previous one
+            _ => Some(self as u8),
+        }
+    }
+}
+
+/// Blue Spheres medals collected so far
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Medals {
+    silver: u8,
+    gold: u8,
+}
+
+impl Medals {
+    fn count(medals: &[u8; 32]) -> Self {
+        Self {
+            silver: medals.iter().filter(|&&medal| medal == 1).count() as u8,
+            gold: medals.iter().filter(|&&medal| medal == 2).count() as u8,
+        }
+    }
+
+    /// Grows every time a medal is earned, including silver medals upgraded to gold
+    fn score(self) -> u8 {
+        self.silver + self.gold * 2
+    }
+}
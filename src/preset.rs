@@ -0,0 +1,220 @@
+//! Presets configure the start, reset and split settings following the rules of a speedrun.com category.
+//!
+//! A preset is applied once, when it gets picked, by writing the settings it covers to the settings map. This way
+//! the settings shown reflect the category, and can still be adjusted by hand afterwards. The timing options, the
+//! act to practise and the debug trace are left alone.
+
+use crate::runtime::SettingsStore;
+use asr::settings::Gui;
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum Preset {
+    /// Custom (use the settings below as they are)
+    #[default]
+    Custom,
+    /// Mania Any%
+    ManiaAnyPercent,
+    /// Knuckles & Knuckles (Mania Any% as Knuckles)
+    KnucklesAndKnuckles,
+    /// Encore Any%
+    EncoreAnyPercent,
+    /// All Emeralds
+    AllEmeralds,
+    /// Individual Zone practice
+    IndividualZone,
+}
+
+/// Value of a setting written by a preset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    /// Choices are stored by the name of their variant
+    Choice(&'static str),
+}
+
+impl Preset {
+    /// Writes every setting of the category to the settings map
+    pub fn apply(self, map: &impl SettingsStore) {
+        let (mania, encore, route) = match self {
+            Self::Custom => return,
+            Self::ManiaAnyPercent => (true, false, "ManiaAnyPercent"),
+            Self::KnucklesAndKnuckles => (true, false, "KnucklesOnly"),
+            Self::AllEmeralds => (true, false, "AllEmeralds"),
+            Self::EncoreAnyPercent => (false, true, "EncoreAnyPercent"),
+            // Practice can start anywhere in the game, and should never reset by accident
+            Self::IndividualZone => (true, true, "AnyOrder"),
+        };
+        let practice = self == Self::IndividualZone;
+        let all_emeralds = self == Self::AllEmeralds;
+        // The save character setting doesn't tell Knuckles & Knuckles apart from Knuckles alone
+        let character = match self {
+            Self::KnucklesAndKnuckles => "Knuckles",
+            _ => "Any",
+        };
+
+        let set = |key, value| map.write(key, value);
+        set("route", Value::Choice(route));
+        set("start_save_character", Value::Choice(character));
+        STAR_POST_SPLITS
+            .iter()
+            .for_each(|key| set(key, Value::Choice("None")));
+
+        let set = |key, value| set(key, Value::Bool(value));
+        set("start_mania_mode", mania && !practice);
+        set("start_encore_mode", encore && !practice);
+        set("start_from_save", false);
+        set("reset_save_select", !practice);
+        set("reset_dev_menu", false);
+        // The act to practise is left to the runner
+        set("practice_mode", practice);
+
+        MANIA_SPLITS.iter().for_each(|key| set(key, mania));
+        ENCORE_SPLITS.iter().for_each(|key| set(key, encore));
+        // Only All Emeralds goes on to Egg Reverie, and splits on every emerald along the way
+        set("egg_reverie", all_emeralds || practice);
+        EMERALD_SPLITS.iter().for_each(|key| set(key, all_emeralds));
+        OPTIONAL_SPLITS.iter().for_each(|key| set(key, false));
+    }
+}
+
+/// Mode, zone and act toggles of Mania mode, up to Titanic Monarch
+const MANIA_SPLITS: [&str; 37] = [
+    "mania_mode",
+    "mania_green_hill",
+    "green_hill_1",
+    "green_hill_2",
+    "mania_chemical_plant",
+    "chemical_plant_1",
+    "chemical_plant_2",
+    "mania_studiopolis",
+    "studiopolis_1",
+    "studiopolis_2",
+    "mania_flying_battery",
+    "flying_battery_1",
+    "flying_battery_2",
+    "mania_press_garden",
+    "press_garden_1",
+    "press_garden_2",
+    "mania_stardust_speedway",
+    "stardust_speedway_1",
+    "stardust_speedway_2",
+    "mania_hydrocity",
+    "hydrocity_1",
+    "hydrocity_2",
+    "mania_mirage_saloon",
+    "mirage_saloon_1",
+    "mirage_saloon_2",
+    "mania_oil_ocean",
+    "oil_ocean_1",
+    "oil_ocean_2",
+    "mania_lava_reef",
+    "lava_reef_1",
+    "lava_reef_2",
+    "mania_metallic_madness",
+    "metallic_madness_1",
+    "metallic_madness_2",
+    "mania_titanic_monarch",
+    "titanic_monarch_1",
+    "titanic_monarch_2",
+];
+
+/// Mode, zone and act toggles of Encore mode
+const ENCORE_SPLITS: [&str; 38] = [
+    "encore_mode",
+    "angel_island",
+    "encore_green_hill",
+    "encore_green_hill_1",
+    "encore_green_hill_2",
+    "encore_chemical_plant",
+    "encore_chemical_plant_1",
+    "encore_chemical_plant_2",
+    "encore_studiopolis",
+    "encore_studiopolis_1",
+    "encore_studiopolis_2",
+    "encore_flying_battery",
+    "encore_flying_battery_1",
+    "encore_flying_battery_2",
+    "encore_press_garden",
+    "encore_press_garden_1",
+    "encore_press_garden_2",
+    "encore_stardust_speedway",
+    "encore_stardust_speedway_1",
+    "encore_stardust_speedway_2",
+    "encore_hydrocity",
+    "encore_hydrocity_1",
+    "encore_hydrocity_2",
+    "encore_mirage_saloon",
+    "encore_mirage_saloon_1",
+    "encore_mirage_saloon_2",
+    "encore_oil_ocean",
+    "encore_oil_ocean_1",
+    "encore_oil_ocean_2",
+    "encore_lava_reef",
+    "encore_lava_reef_1",
+    "encore_lava_reef_2",
+    "encore_metallic_madness",
+    "encore_metallic_madness_1",
+    "encore_metallic_madness_2",
+    "encore_titanic_monarch",
+    "encore_titanic_monarch_1",
+    "encore_titanic_monarch_2",
+];
+
+/// Chaos Emerald splits
+const EMERALD_SPLITS: [&str; 7] = [
+    "chaos_emerald_1",
+    "chaos_emerald_2",
+    "chaos_emerald_3",
+    "chaos_emerald_4",
+    "chaos_emerald_5",
+    "chaos_emerald_6",
+    "chaos_emerald_7",
+];
+
+/// Other splits outside of the acts (Special Stages, bonus stages and bosses)
+const OPTIONAL_SPLITS: [&str; 16] = [
+    "special_stage_failed",
+    "medal_earned",
+    "bonus_stage_failed",
+    "bonus_stage_exited",
+    "boss_green_hill",
+    "boss_chemical_plant",
+    "boss_studiopolis",
+    "boss_flying_battery",
+    "boss_press_garden",
+    "boss_stardust_speedway",
+    "boss_hydrocity",
+    "boss_mirage_saloon",
+    "boss_oil_ocean",
+    "boss_lava_reef",
+    "boss_metallic_madness",
+    "boss_titanic_monarch",
+];
+
+/// Star post splits of every act
+const STAR_POST_SPLITS: [&str; 24] = [
+    "star_posts_green_hill_1",
+    "star_posts_green_hill_2",
+    "star_posts_chemical_plant_1",
+    "star_posts_chemical_plant_2",
+    "star_posts_studiopolis_1",
+    "star_posts_studiopolis_2",
+    "star_posts_flying_battery_1",
+    "star_posts_flying_battery_2",
+    "star_posts_press_garden_1",
+    "star_posts_press_garden_2",
+    "star_posts_stardust_speedway_1",
+    "star_posts_stardust_speedway_2",
+    "star_posts_hydrocity_1",
+    "star_posts_hydrocity_2",
+    "star_posts_mirage_saloon_1",
+    "star_posts_mirage_saloon_2",
+    "star_posts_oil_ocean_1",
+    "star_posts_oil_ocean_2",
+    "star_posts_lava_reef_1",
+    "star_posts_lava_reef_2",
+    "star_posts_metallic_madness_1",
+    "star_posts_metallic_madness_2",
+    "star_posts_titanic_monarch_1",
+    "star_posts_titanic_monarch_2",
+];
@@ -0,0 +1,176 @@
+//! Routes define which act transitions are expected during a run, and therefore trigger a split.
+//!
+//! Every built-in route is a list of chains of acts, played in the order they are listed, following
+//! the speedrun.com categories. They differ in the acts they go through and in how the game ends:
+//! Any% is over when the Titanic Monarch Act 2 boss is defeated, while All Emeralds goes on to Egg
+//! Reverie. Routes jumping around the acts (eg. warping through the level select of a no save file)
+//! are covered by the any order route, where every act not played yet in the run counts as progress.
+
+use crate::Acts;
+use asr::settings::Gui;
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum Route {
+    /// Mania Mode and Encore Mode, ending where the game takes the player
+    #[default]
+    Standard,
+    /// Mania Any% (every character), ending on Titanic Monarch Act 2
+    ManiaAnyPercent,
+    /// All Emeralds, ending on Egg Reverie
+    AllEmeralds,
+    /// Encore Any%
+    EncoreAnyPercent,
+    /// Knuckles only (Mania Any% as Knuckles), ending on Titanic Monarch Act 2
+    KnucklesOnly,
+    /// No Zips (Mania Any% without zips), ending on Titanic Monarch Act 2
+    NoZips,
+    /// Any order (custom routes, marathons, level select warps)
+    AnyOrder,
+}
+
+impl Route {
+    /// Whether the act is played at all in the route
+    pub fn contains(self, act: Acts) -> bool {
+        self.chains().iter().any(|chain| chain.contains(&act))
+    }
+
+    /// Whether moving from one act to the other is a step forward in the route
+    pub fn expects(self, from: Acts, to: Acts) -> bool {
+        // Any other act counts, as long as we stay in the same game mode
+        if self == Self::AnyOrder {
+            return from != to
+                && self
+                    .chains()
+                    .iter()
+                    .any(|chain| chain.contains(&from) && chain.contains(&to));
+        }
+
+        self.chains()
+            .iter()
+            .any(|chain| chain.windows(2).any(|step| step == [from, to]))
+    }
+
+    /// Whether the run goes on to Egg Reverie after Titanic Monarch Act 2, given whether the game
+    /// is about to take the player there
+    pub fn ends_on_egg_reverie(self, true_ending: bool) -> bool {
+        match self {
+            Self::ManiaAnyPercent | Self::EncoreAnyPercent | Self::KnucklesOnly | Self::NoZips => {
+                false
+            }
+            Self::AllEmeralds => true,
+            Self::Standard | Self::AnyOrder => true_ending,
+        }
+    }
+
+    /// Acts played in the route in Mania or Encore mode, in the order they are played
+    pub fn acts(self, encore: bool) -> &'static [Acts] {
+        match (self, encore) {
+            (Self::AllEmeralds, false) => ALL_EMERALDS,
+            (_, false) => MANIA_MODE,
+            (_, true) => ENCORE_MODE,
+        }
+    }
+
+    fn chains(self) -> &'static [&'static [Acts]] {
+        match self {
+            Self::Standard | Self::AnyOrder => &[MANIA_MODE, ENCORE_MODE],
+            // Knuckles plays the same acts, and zips only shorten them: both categories only restrict
+            // how the acts are played
+            Self::ManiaAnyPercent | Self::KnucklesOnly | Self::NoZips => &[MANIA_MODE],
+            Self::AllEmeralds => &[ALL_EMERALDS],
+            Self::EncoreAnyPercent => &[ENCORE_MODE],
+        }
+    }
+}
+
+/// Position of the act in the mode it's played in, starting from 1.
+/// Returns `None` for the acts outside of the route (eg. Egg Reverie).
+pub fn position(act: Acts) -> Option<u8> {
+    [MANIA_MODE, ENCORE_MODE]
+        .iter()
+        .find_map(|chain| chain.iter().position(|&other| other == act))
+        .map(|index| index as u8 + 1)
+}
+
+const MANIA_MODE: &[Acts] = &[
+    Acts::GreenHill1,
+    Acts::GreenHill2,
+    Acts::ChemicalPlant1,
+    Acts::ChemicalPlant2,
+    Acts::Studiopolis1,
+    Acts::Studiopolis2,
+    Acts::FlyingBattery1,
+    Acts::FlyingBattery2,
+    Acts::PressGarden1,
+    Acts::PressGarden2,
+    Acts::StardustSpeedway1,
+    Acts::StardustSpeedway2,
+    Acts::Hydrocity1,
+    Acts::Hydrocity2,
+    Acts::MirageSaloon1,
+    Acts::MirageSaloon2,
+    Acts::OilOcean1,
+    Acts::OilOcean2,
+    Acts::LavaReef1,
+    Acts::LavaReef2,
+    Acts::MetallicMadness1,
+    Acts::MetallicMadness2,
+    Acts::TitanicMonarch1,
+    Acts::TitanicMonarch2,
+];
+
+const ALL_EMERALDS: &[Acts] = &[
+    Acts::GreenHill1,
+    Acts::GreenHill2,
+    Acts::ChemicalPlant1,
+    Acts::ChemicalPlant2,
+    Acts::Studiopolis1,
+    Acts::Studiopolis2,
+    Acts::FlyingBattery1,
+    Acts::FlyingBattery2,
+    Acts::PressGarden1,
+    Acts::PressGarden2,
+    Acts::StardustSpeedway1,
+    Acts::StardustSpeedway2,
+    Acts::Hydrocity1,
+    Acts::Hydrocity2,
+    Acts::MirageSaloon1,
+    Acts::MirageSaloon2,
+    Acts::OilOcean1,
+    Acts::OilOcean2,
+    Acts::LavaReef1,
+    Acts::LavaReef2,
+    Acts::MetallicMadness1,
+    Acts::MetallicMadness2,
+    Acts::TitanicMonarch1,
+    Acts::TitanicMonarch2,
+    Acts::EggReverie,
+];
+
+const ENCORE_MODE: &[Acts] = &[
+    Acts::EncoreAngelIsland,
+    Acts::EncoreGreenHill1,
+    Acts::EncoreGreenHill2,
+    Acts::EncoreChemicalPlant1,
+    Acts::EncoreChemicalPlant2,
+    Acts::EncoreStudiopolis1,
+    Acts::EncoreStudiopolis2,
+    Acts::EncoreFlyingBattery1,
+    Acts::EncoreFlyingBattery2,
+    Acts::EncorePressGarden1,
+    Acts::EncorePressGarden2,
+    Acts::EncoreStardustSpeedway1,
+    Acts::EncoreStardustSpeedway2,
+    Acts::EncoreHydrocity1,
+    Acts::EncoreHydrocity2,
+    Acts::EncoreMirageSaloon1,
+    Acts::EncoreMirageSaloon2,
+    Acts::EncoreOilOcean1,
+    Acts::EncoreOilOcean2,
+    Acts::EncoreLavaReef1,
+    Acts::EncoreLavaReef2,
+    Acts::EncoreMetallicMadness1,
+    Acts::EncoreMetallicMadness2,
+    Acts::EncoreTitanicMonarch1,
+    Acts::EncoreTitanicMonarch2,
+];
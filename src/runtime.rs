@@ -0,0 +1,78 @@
+//! Messages, timer variables and settings sent to the runtime.
+//!
+//! The replay tests run on the host, where the runtime's imports can't be linked, so there they get
+//! recorded instead and can be inspected with [`variable`], [`messages`] and [`SettingsMap`].
+
+use crate::preset::Value;
+use asr::settings::Map;
+
+#[cfg(not(test))]
+pub use asr::{print_message, timer::set_variable};
+
+/// Storage for the settings written by a preset
+pub trait SettingsStore {
+    fn write(&self, key: &'static str, value: Value);
+}
+
+impl SettingsStore for Map {
+    fn write(&self, key: &'static str, value: Value) {
+        match value {
+            Value::Bool(value) => self.insert(key, &value.into()),
+            Value::Choice(value) => self.insert(key, &value.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+use std::{cell::RefCell, collections::BTreeMap};
+
+#[cfg(test)]
+thread_local! {
+    static VARIABLES: RefCell<BTreeMap<String, String>> = RefCell::default();
+    static MESSAGES: RefCell<Vec<String>> = RefCell::default();
+}
+
+#[cfg(test)]
+pub fn set_variable(key: &str, value: &str) {
+    VARIABLES.with(|variables| variables.borrow_mut().insert(key.into(), value.into()));
+}
+
+#[cfg(test)]
+pub fn print_message(message: &str) {
+    MESSAGES.with(|messages| messages.borrow_mut().push(message.into()));
+}
+
+/// Last value set for the timer variable
+#[cfg(test)]
+pub fn variable(key: &str) -> Option<String> {
+    VARIABLES.with(|variables| variables.borrow().get(key).cloned())
+}
+
+/// Messages printed so far
+#[cfg(test)]
+pub fn messages() -> Vec<String> {
+    MESSAGES.with(|messages| messages.borrow().clone())
+}
+
+/// Settings map kept in memory, standing in for the runtime's one
+#[cfg(test)]
+#[derive(Default)]
+pub struct SettingsMap(RefCell<BTreeMap<&'static str, Value>>);
+
+#[cfg(test)]
+impl SettingsMap {
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.0.borrow().get(key).copied()
+    }
+
+    pub fn keys(&self) -> Vec<&'static str> {
+        self.0.borrow().keys().copied().collect()
+    }
+}
+
+#[cfg(test)]
+impl SettingsStore for SettingsMap {
+    fn write(&self, key: &'static str, value: Value) {
+        self.0.borrow_mut().insert(key, value);
+    }
+}
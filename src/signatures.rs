@@ -0,0 +1,619 @@
+//! Declarative description of where the values we need are found in the game's code.
+//!
+//! Every address is located by a [`Target`]: a signature scanned in one of the game's modules, the
+//! distance between the match and the pointer embedded in the instruction, how that pointer is
+//! encoded and which additional values (usually the offsets of a pointer path) are read next to it.
+//! The targets, the save data offsets and the global variable offsets are grouped in a [`Layout`],
+//! selected from the detected [`GameVariant`], so supporting a new build should only require adding
+//! a row to [`LAYOUTS`].
+
+use crate::{runtime, Build, GameVariant};
+use arrayvec::ArrayString;
+use asr::{future::next_tick, signature::Signature, Address, Address32, Process};
+use core::fmt::Write;
+
+/// Time given to the game to load its modules before we give up on the signatures we couldn't find.
+/// About 10 seconds at the default tick rate.
+const SCAN_TIMEOUT_TICKS: u32 = 1200;
+
+/// Signature of any length, so signatures of different sizes can live in the same table
+pub trait Scan {
+    fn scan(&self, process: &Process, range: (Address, u64)) -> Option<Address>;
+}
+
+impl<const N: usize> Scan for Signature<N> {
+    fn scan(&self, process: &Process, range: (Address, u64)) -> Option<Address> {
+        self.scan_process_range(process, range)
+    }
+}
+
+/// Address and size of the modules signatures get scanned in
+pub struct Modules {
+    pub main: (Address, u64),
+    /// Game.dll on the decompilation. On the retail version this is the main module.
+    pub game: (Address, u64),
+}
+
+#[derive(Clone, Copy)]
+pub enum Module {
+    Main,
+    Game,
+}
+
+/// How the pointer embedded in the instruction is encoded
+#[derive(Clone, Copy)]
+pub enum Pointer {
+    /// 32-bit absolute address
+    Absolute,
+    /// 32-bit displacement from the end of the pointer itself (RIP-relative addressing)
+    Relative,
+    /// We are only interested in the values read next to the match
+    None,
+}
+
+/// Value read at the specified distance from the pointer
+#[derive(Clone, Copy)]
+pub enum Read {
+    U8(u32),
+    U32(u32),
+}
+
+pub struct Target {
+    /// Reported when the signature can't be found
+    pub name: &'static str,
+    pub signature: &'static dyn Scan,
+    pub module: Module,
+    /// Distance between the start of the signature and the pointer
+    pub offset: u32,
+    pub pointer: Pointer,
+    pub reads: &'static [Read],
+}
+
+#[derive(Default)]
+pub struct Resolved {
+    pub address: Address,
+    pub values: [u32; 2],
+}
+
+impl Target {
+    fn try_resolve(&self, process: &Process, modules: &Modules) -> Option<Resolved> {
+        let range = match self.module {
+            Module::Main => modules.main,
+            Module::Game => modules.game,
+        };
+
+        let ptr = self.signature.scan(process, range)? + self.offset;
+        let address = match self.pointer {
+            Pointer::Absolute => process.read::<Address32>(ptr).ok()?.into(),
+            Pointer::Relative => ptr + 0x4 + process.read::<i32>(ptr).ok()?,
+            Pointer::None => ptr,
+        };
+
+        let mut values = [0; 2];
+        for (value, read) in values.iter_mut().zip(self.reads) {
+            *value = match *read {
+                Read::U8(offset) => process.read::<u8>(ptr + offset).ok()? as u32,
+                Read::U32(offset) => process.read::<u32>(ptr + offset).ok()?,
+            };
+        }
+
+        Some(Resolved { address, values })
+    }
+}
+
+/// Resolves targets, giving up on the ones that can't be found instead of hanging forever
+pub struct Scanner<'a> {
+    process: &'a Process,
+    modules: &'a Modules,
+    /// Ticks left before giving up. Shared between all the targets, so a few missing signatures
+    /// don't add up to a longer wait.
+    ticks_left: u32,
+    /// Names of the targets that couldn't be found
+    pub missing: ArrayString<256>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(process: &'a Process, modules: &'a Modules) -> Self {
+        Self {
+            process,
+            modules,
+            ticks_left: SCAN_TIMEOUT_TICKS,
+            missing: ArrayString::new(),
+        }
+    }
+
+    pub async fn resolve(&mut self, target: &Target) -> Option<Resolved> {
+        loop {
+            if let Some(resolved) = target.try_resolve(self.process, self.modules) {
+                return Some(resolved);
+            }
+
+            if self.ticks_left == 0 {
+                give_up(target, &mut self.missing);
+                return None;
+            }
+
+            self.ticks_left -= 1;
+            next_tick().await;
+        }
+    }
+}
+
+/// Resolves targets a little on every tick, so the autosplitter keeps running while the game is
+/// still loading the module they're in
+pub struct PendingScan<const N: usize> {
+    resolved: [Option<Resolved>; N],
+    ticks_left: u32,
+    /// Names of the targets that couldn't be found
+    pub missing: ArrayString<256>,
+}
+
+impl<const N: usize> PendingScan<N> {
+    pub fn new() -> Self {
+        Self {
+            resolved: core::array::from_fn(|_| None),
+            ticks_left: SCAN_TIMEOUT_TICKS,
+            missing: ArrayString::new(),
+        }
+    }
+
+    /// Looks again for the targets not found yet. Once they're all found, or we gave up on the
+    /// missing ones, returns them in the same order with the missing ones, and the ones without a
+    /// target, left to default.
+    pub fn poll(
+        &mut self,
+        process: &Process,
+        modules: &Modules,
+        targets: [Option<&Target>; N],
+    ) -> Option<[Resolved; N]> {
+        for (resolved, target) in self.resolved.iter_mut().zip(targets) {
+            if resolved.is_none() {
+                *resolved = match target {
+                    Some(target) => target.try_resolve(process, modules),
+                    None => Some(Resolved::default()),
+                };
+            }
+        }
+
+        if self.resolved.iter().any(Option::is_none) {
+            if self.ticks_left > 0 {
+                self.ticks_left -= 1;
+                return None;
+            }
+            for (resolved, target) in self.resolved.iter().zip(targets) {
+                if let (None, Some(target)) = (resolved, target) {
+                    give_up(target, &mut self.missing);
+                }
+            }
+        }
+
+        Some(
+            self.resolved
+                .each_mut()
+                .map(|resolved| resolved.take().unwrap_or_default()),
+        )
+    }
+}
+
+fn give_up(target: &Target, missing: &mut ArrayString<256>) {
+    let mut message = ArrayString::<64>::new();
+    let _ = write!(message, "Signature not found: {}", target.name);
+    runtime::print_message(&message);
+
+    if !missing.is_empty() {
+        let _ = missing.try_push_str(", ");
+    }
+    let _ = missing.try_push_str(target.name);
+}
+
+/// Targets shared by all the builds of the same architecture
+pub struct Targets {
+    /// Only found in the main module of the RSDKv5U builds
+    pub rsdk_5u: &'static dyn Scan,
+    /// LevelID. Actually a SceneID, it also identifies whenever we are in the main menu
+    pub level_id: Target,
+    /// Status and in-game timer
+    pub status: Target,
+    /// Health of the first boss in Egg Reverie
+    pub egg_reverie_1: Target,
+    /// Offsets to the health of the second boss in Egg Reverie
+    pub egg_reverie_2: Target,
+    /// Flag that immediately tells when we defeated Eggman in Titanic Monarch Act 2
+    pub egg_monarch: Target,
+    pub egg_monarch_offset: Target,
+    /// Bitmask for the Chaos Emeralds. One bit for every emerald. 0x7F = 7 emeralds.
+    pub chaos_emeralds: Target,
+    /// Characters, also used for the game mode and the offsets in [`Globals`]
+    pub characters: Target,
+    /// Base for the offsets in [`Saves`]
+    pub save_data: Target,
+}
+
+/// Offsets from the save data to the values we use for starting and resetting the timer
+pub struct Saves {
+    /// Monitors whenever the game reports a successful selection of the save file.
+    /// Very janky solution, but it works.
+    pub start_trigger: u32,
+    pub mania_mode_selection: u32,
+    pub save_selection_mania: u32,
+    /// First Mania save slot
+    pub mania_slots: u32,
+    pub save_selection_encore: u32,
+    /// Encore save slots, counting from a slot 0 which doesn't exist in the menu
+    pub encore_slots: u32,
+    /// Distance between two save slots
+    pub slot_size: u32,
+}
+
+/// Offsets from the game's global variables, found through the characters target, to the values
+/// read from there
+pub struct Globals {
+    /// Reserve characters (Encore mode). Every character is stored in a separate byte, with the
+    /// next one to be used in the lowest one.
+    pub stock: u32,
+    /// Blue Spheres medals, stored together with the rest of the game's progress. Every bonus stage
+    /// has its own 32-bit value: 0 = none, 1 = silver, 2 = gold.
+    pub medals: u32,
+    /// Entity slot of the last star post touched by the player. The game uses it to respawn the
+    /// player, and clears it when a new act starts.
+    pub star_post: u32,
+    /// Save slots. Every slot takes `save_slot_size` bytes and starts with three 32-bit values: its
+    /// state (0 = empty, 1 = in progress, 2 = completed), the character and the zone reached.
+    pub save_slots: u32,
+    pub save_slot_size: u32,
+    /// Index of the first Encore mode save slot, stored after the Mania mode ones
+    pub encore_save_slots: u32,
+    /// Offset, within an Encore mode save slot, of the team it is played with: the ID of the leader
+    /// in the first byte and the one of the buddy in the next
+    pub save_team: u32,
+}
+
+pub struct Layout {
+    pub builds: &'static [Build],
+    pub is_64_bit: bool,
+    pub targets: &'static Targets,
+    pub saves: Saves,
+    pub globals: Globals,
+    /// Flag set while the results screen of an act is shown. No signature has been found for it on
+    /// any build yet, so the act clear is told from the act timer staying stopped until then.
+    pub results_screen: Option<Target>,
+    /// Health of the Act 2 bosses, indexed by [`Boss`](crate::Boss): the pointer to the boss object, then the
+    /// offsets to its entity and to its health. No signature has been found for them on any build
+    /// yet, so the boss splits fall back to the act clear until they get added here.
+    pub bosses: [Option<Target>; 12],
+}
+
+impl Layout {
+    /// Targets found in the game module, which get scanned for again every time it's reloaded
+    pub fn game_targets(&self) -> [Option<&Target>; 21] {
+        let targets = self.targets;
+        let mut game_targets = [None; 21];
+        for (game_target, target) in game_targets.iter_mut().zip([
+            &targets.level_id,
+            &targets.status,
+            &targets.egg_reverie_1,
+            &targets.egg_reverie_2,
+            &targets.egg_monarch,
+            &targets.egg_monarch_offset,
+            &targets.chaos_emeralds,
+            &targets.characters,
+        ]) {
+            *game_target = Some(target);
+        }
+        game_targets[8] = self.results_screen.as_ref();
+        for (game_target, boss) in game_targets[9..].iter_mut().zip(&self.bosses) {
+            *game_target = boss.as_ref();
+        }
+        game_targets
+    }
+
+    /// Picks the layout of the specified variant. Unknown combinations fall back to the first
+    /// layout with the same architecture.
+    pub fn find(variant: GameVariant) -> &'static Self {
+        let same_arch = |layout: &&Layout| layout.is_64_bit == variant.is_64_bit;
+        LAYOUTS
+            .iter()
+            .filter(same_arch)
+            .find(|layout| layout.builds.contains(&variant.build))
+            .or_else(|| LAYOUTS.iter().find(same_arch))
+            .unwrap_or(&LAYOUTS[0])
+    }
+}
+
+/// Detects if we're on rsdk5U or not. It alters some of the offsets.
+/// The retail version of the game is NEVER rsdk5U.
+pub fn is_rsdk_5u(process: &Process, main_module: (Address, u64), is_64_bit: bool) -> bool {
+    let targets = if is_64_bit { &X64 } else { &X86 };
+    targets.rsdk_5u.scan(process, main_module).is_some()
+}
+
+pub const LAYOUTS: &[Layout] = &[
+    Layout {
+        builds: &[Build::Steam, Build::Epic, Build::Origin, Build::RsdkV5],
+        is_64_bit: false,
+        targets: &X86,
+        saves: Saves {
+            start_trigger: 0x71808,
+            mania_mode_selection: 0x720AC,
+            save_selection_mania: 0x720C0,
+            mania_slots: 0x146DC,
+            save_selection_encore: 0x7A768,
+            encore_slots: 0x6F5F4,
+            slot_size: 0x458,
+        },
+        globals: Globals {
+            stock: 0x441784,
+            medals: 0x125FC,
+            star_post: 0x41700,
+            save_slots: 0x100A4,
+            save_slot_size: 0x400,
+            encore_save_slots: 10,
+            save_team: 0xB8,
+        },
+        results_screen: None,
+        bosses: NO_BOSSES,
+    },
+    Layout {
+        builds: &[Build::RsdkV5U],
+        is_64_bit: false,
+        targets: &X86,
+        saves: Saves {
+            start_trigger: 0x71808 + 0xD14,
+            mania_mode_selection: 0x720AC + 0xD24,
+            save_selection_mania: 0x720C0 + 0xD24,
+            mania_slots: 0x146DC + 0x25C,
+            save_selection_encore: 0x7A768 + 0xE1C,
+            encore_slots: 0x6F5F4 + 0xCD4,
+            slot_size: 0x460,
+        },
+        globals: Globals {
+            stock: 0x441784,
+            medals: 0x125FC,
+            star_post: 0x41700,
+            save_slots: 0x100A4,
+            save_slot_size: 0x400,
+            encore_save_slots: 10,
+            save_team: 0xB8,
+        },
+        results_screen: None,
+        bosses: NO_BOSSES,
+    },
+    // 64-bit builds only exist for the decomps
+    Layout {
+        builds: &[Build::RsdkV5],
+        is_64_bit: true,
+        targets: &X64,
+        saves: Saves {
+            start_trigger: 0xD977A,
+            mania_mode_selection: 0xDA81E,
+            save_selection_mania: 0xDA836,
+            mania_slots: 0x26A9A,
+            save_selection_encore: 0xEAADE,
+            encore_slots: 0xD55AA,
+            slot_size: 0x858,
+        },
+        globals: Globals {
+            stock: 0x441784,
+            medals: 0x125FC,
+            star_post: 0x41700,
+            save_slots: 0x100A4,
+            save_slot_size: 0x400,
+            encore_save_slots: 10,
+            save_team: 0xB8,
+        },
+        results_screen: None,
+        bosses: NO_BOSSES,
+    },
+    Layout {
+        builds: &[Build::RsdkV5U],
+        is_64_bit: true,
+        targets: &X64,
+        saves: Saves {
+            start_trigger: 0xD977A + 0x1A10,
+            mania_mode_selection: 0xDA81E + 0x1A30,
+            save_selection_mania: 0xDA836 + 0x1A30,
+            mania_slots: 0x26A9A + 0x4A0,
+            save_selection_encore: 0xEAADE + 0x1C20,
+            encore_slots: 0xD55AA + 0x1990,
+            slot_size: 0x868,
+        },
+        globals: Globals {
+            stock: 0x441784,
+            medals: 0x125FC,
+            star_post: 0x41700,
+            save_slots: 0x100A4,
+            save_slot_size: 0x400,
+            encore_save_slots: 10,
+            save_team: 0xB8,
+        },
+        results_screen: None,
+        bosses: NO_BOSSES,
+    },
+];
+
+/// For the builds where the health of the bosses hasn't been found yet
+const NO_BOSSES: [Option<Target>; 12] = {
+    const NONE: Option<Target> = None;
+    [NONE; 12]
+};
+
+const X86: Targets = {
+    const SIG_RSDK5U: Signature<24> =
+        Signature::new("3D ???????? 0F 87 ???????? FF 24 85 ???????? A1 ???????? 89");
+    const SIG: Signature<14> = Signature::new("8B ?? ???????? 85 C9 74 1E 80 ?? ?? 03");
+    const SIG_STATUS: Signature<16> = Signature::new("A1 ???????? 80 78 ?? 01 0F 85 ???????? FF");
+    const SIG_ER: Signature<13> = Signature::new("8B ?? ???????? 8B ?? 40 83 ?? ?? 00");
+    const SIG_EM1: Signature<8> = Signature::new("A1 ???????? 83 C0 78");
+    const SIG_EM2: Signature<24> =
+        Signature::new("6A 00 C7 80 ???????? 01 00 00 00 A1 ???????? C7 ?? ?? 00 00 00 00");
+    const SIG_CE: Signature<14> = Signature::new("7D ?? A1 ???????? 8B ?? ?? 83 ?? ?? 7F");
+    const SIG_CHARACTER: Signature<10> = Signature::new("8B 15 ???????? 8B 4A 04 C1");
+    const SIG_SAVE: Signature<14> = Signature::new("69 C0 ???????? 05 ???????? 5D C3 CC");
+
+    Targets {
+        rsdk_5u: &SIG_RSDK5U,
+        level_id: Target {
+            name: "Level ID",
+            signature: &SIG,
+            module: Module::Game,
+            offset: 2,
+            pointer: Pointer::Absolute,
+            reads: &[Read::U8(28)],
+        },
+        status: Target {
+            name: "Status",
+            signature: &SIG_STATUS,
+            module: Module::Game,
+            offset: 1,
+            pointer: Pointer::Absolute,
+            reads: &[Read::U8(6)],
+        },
+        egg_reverie_1: Target {
+            name: "Egg Reverie boss",
+            signature: &SIG_ER,
+            module: Module::Game,
+            offset: 2,
+            pointer: Pointer::Absolute,
+            reads: &[Read::U8(6), Read::U8(9)],
+        },
+        egg_reverie_2: Target {
+            name: "Egg Reverie Eggman",
+            signature: &SIG_ER,
+            module: Module::Game,
+            offset: 2,
+            pointer: Pointer::None,
+            reads: &[Read::U8(15), Read::U8(18)],
+        },
+        egg_monarch: Target {
+            name: "Titanic Monarch Eggman",
+            signature: &SIG_EM1,
+            module: Module::Game,
+            offset: 1,
+            pointer: Pointer::Absolute,
+            reads: &[],
+        },
+        egg_monarch_offset: Target {
+            name: "Titanic Monarch Eggman offset",
+            signature: &SIG_EM2,
+            module: Module::Game,
+            offset: 4,
+            pointer: Pointer::None,
+            reads: &[Read::U32(0)],
+        },
+        chaos_emeralds: Target {
+            name: "Chaos Emeralds",
+            signature: &SIG_CE,
+            module: Module::Game,
+            offset: 3,
+            pointer: Pointer::Absolute,
+            reads: &[Read::U8(6), Read::U8(9)],
+        },
+        characters: Target {
+            name: "Characters",
+            signature: &SIG_CHARACTER,
+            module: Module::Game,
+            offset: 2,
+            pointer: Pointer::Absolute,
+            reads: &[Read::U8(6)],
+        },
+        save_data: Target {
+            name: "Save data",
+            signature: &SIG_SAVE,
+            module: Module::Main,
+            offset: 7,
+            pointer: Pointer::Absolute,
+            reads: &[],
+        },
+    }
+};
+
+const X64: Targets = {
+    const SIG_RSDK5U: Signature<15> = Signature::new("81 F9 ???????? 0F 87 ???????? 41 8B 8C");
+    const SIG: Signature<14> = Signature::new("48 8B ?? ???????? 48 85 C9 74 2A 80 7A");
+    const SIG_STATUS: Signature<18> =
+        Signature::new("48 8B ?? ???????? 80 78 ?? 01 0F 85 ???????? FF");
+    const SIG_ER: Signature<14> = Signature::new("75 ?? 48 8B 0D ???????? 48 8B 41 ?? 83");
+    const SIG_EM: Signature<20> =
+        Signature::new("89 73 ?? 48 8B ?? ???????? C7 ?? ???????? 01 00 00 00");
+    const SIG_CE: Signature<18> =
+        Signature::new("7D ?? 48 8B ?? ???????? 48 ?? ?? ?? 83 ?? ?? 7F 7C");
+    const SIG_CHARACTER: Signature<13> = Signature::new("48 8B ?? ???????? 8B 4A ?? C1 F9 08");
+    const SIG_SAVE: Signature<18> =
+        Signature::new("4C 8D ?? ???????? 8B D0 4C 8D ?? ???????? 0F 1F");
+
+    Targets {
+        rsdk_5u: &SIG_RSDK5U,
+        level_id: Target {
+            name: "Level ID",
+            signature: &SIG,
+            module: Module::Game,
+            offset: 3,
+            pointer: Pointer::Relative,
+            reads: &[Read::U8(30)],
+        },
+        status: Target {
+            name: "Status",
+            signature: &SIG_STATUS,
+            module: Module::Game,
+            offset: 3,
+            pointer: Pointer::Relative,
+            reads: &[Read::U8(6)],
+        },
+        egg_reverie_1: Target {
+            name: "Egg Reverie boss",
+            signature: &SIG_ER,
+            module: Module::Game,
+            offset: 5,
+            pointer: Pointer::Relative,
+            reads: &[Read::U8(7), Read::U8(10)],
+        },
+        egg_reverie_2: Target {
+            name: "Egg Reverie Eggman",
+            signature: &SIG_ER,
+            module: Module::Game,
+            offset: 0,
+            pointer: Pointer::None,
+            reads: &[Read::U8(5), Read::U8(8)],
+        },
+        egg_monarch: Target {
+            name: "Titanic Monarch Eggman",
+            signature: &SIG_EM,
+            module: Module::Game,
+            offset: 6,
+            pointer: Pointer::Relative,
+            reads: &[],
+        },
+        egg_monarch_offset: Target {
+            name: "Titanic Monarch Eggman offset",
+            signature: &SIG_EM,
+            module: Module::Game,
+            offset: 6,
+            pointer: Pointer::None,
+            reads: &[Read::U32(6)],
+        },
+        chaos_emeralds: Target {
+            name: "Chaos Emeralds",
+            signature: &SIG_CE,
+            module: Module::Game,
+            offset: 5,
+            pointer: Pointer::Relative,
+            reads: &[Read::U8(7), Read::U8(10)],
+        },
+        characters: Target {
+            name: "Characters",
+            signature: &SIG_CHARACTER,
+            module: Module::Game,
+            offset: 3,
+            pointer: Pointer::Relative,
+            reads: &[Read::U8(6)],
+        },
+        save_data: Target {
+            name: "Save data",
+            signature: &SIG_SAVE,
+            module: Module::Main,
+            offset: 3,
+            pointer: Pointer::Relative,
+            reads: &[],
+        },
+    }
+};
@@ -0,0 +1,1299 @@
+//! Replay harness for the splitting logic.
+//!
+//! Sequences of raw values, as they would be read from the game's memory, are fed tick by tick
+//! through the same `Watchers::update` used by the autosplitter, while `start`, `split` and `reset`
+//! get evaluated in the same order LiveSplit would do.
+//!
+//! The tests need to be compiled for the host, eg. `cargo test --target x86_64-pc-windows-msvc`.
+
+use super::*;
+use std::any::Any;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Start,
+    Split,
+    Undo,
+    Reset,
+}
+
+/// Replays the recorded snapshots and returns the ticks on which the timer has been started, split or reset
+fn replay(snapshots: &[Snapshot], settings: &Settings) -> Vec<(usize, Action)> {
+    let mut watchers = Watchers::default();
+    let mut running = false;
+    let mut actions = Vec::new();
+
+    for (tick, snapshot) in snapshots.iter().enumerate() {
+        watchers.update(snapshot);
+
+        if running {
+            if reset(&watchers, settings) {
+                running = false;
+                actions.push((tick, Action::Reset));
+            } else if undo_split(&mut watchers, settings) {
+                actions.push((tick, Action::Undo));
+            } else if split(&watchers, settings) {
+                actions.push((tick, Action::Split));
+            }
+        }
+
+        if !running && start(&watchers, settings) {
+            running = true;
+            actions.push((tick, Action::Start));
+        }
+    }
+
+    actions
+}
+
+/// Loads a trace printed by the autosplitter, filling in the ticks on which nothing changed
+fn load_trace(text: &str) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+    for (tick, snapshot) in text.lines().filter_map(trace::parse_line) {
+        if let Some(&last) = snapshots.last() {
+            snapshots.resize(tick as usize, last);
+        }
+        snapshots.push(snapshot);
+    }
+    snapshots
+}
+
+/// Default value of a setting, given the attributes of its field: bools need a `#[default = ...]`,
+/// anything else uses its own default
+pub fn registered_default<T: Default + Any>(attributes: &[&str]) -> T {
+    let mut value = T::default();
+    if let Some(value) = (&mut value as &mut dyn Any).downcast_mut::<bool>() {
+        *value = attributes.contains(&"default = true");
+    }
+    value
+}
+
+const MANIA: u8 = 0;
+const ENCORE: u8 = 1;
+const TIME_ATTACK: u8 = 2;
+
+impl Snapshot {
+    /// Main menu, with the game reporting a Mania mode no-save selection
+    fn main_menu() -> Self {
+        Self {
+            level_id: 2,
+            status: [1, 0, 0, 0, 0],
+            mania_mode_selection: 1,
+            save_selection_mania: 8,
+            characters: [1, 2],
+            ..Default::default()
+        }
+    }
+
+    /// Stage being loaded, with the act timer reset to zero
+    fn loading(game_mode: u8, level_id: u8) -> Self {
+        Self {
+            game_mode,
+            level_id,
+            status: [0, 0, 0, 0, 0],
+            characters: [1, 2],
+            ..Default::default()
+        }
+    }
+
+    /// Gameplay, with the act timer set to the specified amount of centiseconds
+    fn playing(game_mode: u8, level_id: u8, centiseconds: u32) -> Self {
+        Self {
+            game_mode,
+            level_id,
+            status: [
+                1,
+                0,
+                (centiseconds % 100) as u8,
+                (centiseconds / 100 % 60) as u8,
+                (centiseconds / 6000) as u8,
+            ],
+            characters: [1, 2],
+            ..Default::default()
+        }
+    }
+
+    fn with_status(mut self, status: u8) -> Self {
+        self.status[0] = status;
+        self
+    }
+}
+
+/// Builds the sequence of snapshots, tick by tick
+#[derive(Default)]
+struct Recording(Vec<Snapshot>);
+
+impl Recording {
+    fn push(mut self, snapshot: Snapshot, ticks: usize) -> Self {
+        self.0.extend(core::iter::repeat_n(snapshot, ticks));
+        self
+    }
+
+    /// Plays the specified act, with the timer running from `from` to `to` centiseconds
+    fn play(mut self, game_mode: u8, level_id: u8, from: u32, to: u32) -> Self {
+        self.0
+            .extend((from..=to).map(|cs| Snapshot::playing(game_mode, level_id, cs)));
+        self
+    }
+
+    /// Applies a change to every snapshot recorded so far
+    fn map(mut self, f: impl Fn(&mut Snapshot)) -> Self {
+        self.0.iter_mut().for_each(f);
+        self
+    }
+
+    /// The tick the next snapshot will be recorded at
+    fn tick(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Selects a save file in the main menu, which starts a new game
+    fn new_game(self) -> Self {
+        let menu = Snapshot::main_menu();
+        self.push(menu, 5).push(
+            Snapshot {
+                start_trigger: 48,
+                ..menu
+            },
+            1,
+        )
+    }
+
+    /// Goes through the results screen, with the act timer stopped at `cs` centiseconds
+    fn results(self, game_mode: u8, level_id: u8, cs: u32) -> Self {
+        self.push(
+            Snapshot::playing(game_mode, level_id, cs),
+            ACT_CLEAR_TICKS as usize + 60,
+        )
+    }
+
+    /// Dies with the act timer at `cs` centiseconds. The timer stops during the death animation,
+    /// then the act gets reloaded
+    fn die(self, game_mode: u8, level_id: u8, cs: u32) -> Self {
+        self.push(Snapshot::playing(game_mode, level_id, cs), 240)
+    }
+
+    /// Restarts from the pause menu with the act timer at `cs` centiseconds. The game stays paused
+    /// until the act gets reloaded
+    fn restart(self, game_mode: u8, level_id: u8, cs: u32) -> Self {
+        self.push(
+            Snapshot::playing(game_mode, level_id, cs).with_status(3),
+            60,
+        )
+        .load(game_mode, level_id)
+    }
+
+    /// Loads an act and goes through the title card, with the timer at zero
+    fn load(self, game_mode: u8, level_id: u8) -> Self {
+        self.push(Snapshot::loading(game_mode, level_id), 5)
+            .push(Snapshot::loading(game_mode, level_id).with_status(2), 5)
+    }
+}
+
+#[test]
+fn starts_on_new_game() {
+    let recording = Recording::default().new_game();
+    let start_tick = recording.tick() - 1;
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(start_tick, Action::Start)]
+    );
+}
+
+#[test]
+fn does_not_start_on_a_used_save_file() {
+    let recording = Recording::default().new_game().map(|snapshot| {
+        snapshot.save_selection_mania = 3;
+        snapshot.saves_mania[3] = 7;
+    });
+
+    assert_eq!(replay(&recording.0, &Settings::default()), []);
+}
+
+#[test]
+fn starts_on_a_save_matching_the_starting_point() {
+    // Knuckles on Flying Battery
+    let recording = Recording::default().new_game().map(|snapshot| {
+        snapshot.save_selection_mania = 3;
+        snapshot.saves_mania[3] = 3;
+        snapshot.save_progress = [1, 3, 3];
+    });
+
+    let settings = Settings {
+        start_from_save: true,
+        start_save_zone: Zone::FlyingBattery,
+        start_save_character: SaveCharacter::Knuckles,
+        ..Settings::default()
+    };
+    assert_eq!(replay(&recording.0, &settings), [(5, Action::Start)]);
+
+    let settings = Settings {
+        start_save_character: SaveCharacter::Sonic,
+        ..settings
+    };
+    assert_eq!(replay(&recording.0, &settings), []);
+}
+
+#[test]
+fn starts_on_an_encore_save_led_by_the_chosen_character() {
+    // Knuckles leading Mighty on Flying Battery
+    let recording = Recording::default().new_game().map(|snapshot| {
+        snapshot.game_mode = ENCORE;
+        snapshot.mania_mode_selection = 0;
+        snapshot.save_selection_encore = 2;
+        snapshot.save_progress = [1, 0, 3];
+        snapshot.save_team = [4, 8];
+    });
+
+    let settings = Settings {
+        start_from_save: true,
+        start_save_zone: Zone::FlyingBattery,
+        start_save_character: SaveCharacter::Knuckles,
+        ..Settings::default()
+    };
+    assert_eq!(replay(&recording.0, &settings), [(5, Action::Start)]);
+
+    let settings = Settings {
+        start_save_character: SaveCharacter::Mighty,
+        ..settings
+    };
+    assert_eq!(replay(&recording.0, &settings), []);
+}
+
+#[test]
+fn does_not_start_when_disabled() {
+    let recording = Recording::default().new_game();
+    let settings = Settings {
+        start_mania_mode: false,
+        ..Settings::default()
+    };
+
+    assert_eq!(replay(&recording.0, &settings), []);
+}
+
+#[test]
+fn splits_when_the_next_act_is_loaded() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .push(Snapshot::playing(MANIA, 9, 300), 30)
+        .push(Snapshot::loading(MANIA, 10), 5);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(Snapshot::loading(MANIA, 10).with_status(2), 5)
+        .play(MANIA, 10, 0, 100);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn does_not_split_on_disabled_acts() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100);
+    let settings = Settings {
+        green_hill_1: false,
+        ..Settings::default()
+    };
+
+    assert_eq!(replay(&recording.0, &settings), [(5, Action::Start)]);
+}
+
+/// Settings map holding what the preset writes
+fn apply(preset: Preset) -> runtime::SettingsMap {
+    let map = runtime::SettingsMap::default();
+    preset.apply(&map);
+    map
+}
+
+#[test]
+fn presets_set_the_start_reset_and_split_settings() {
+    use preset::Value::{Bool, Choice};
+
+    let map = apply(Preset::AllEmeralds);
+    assert_eq!(map.get("start_mania_mode"), Some(Bool(true)));
+    assert_eq!(map.get("start_encore_mode"), Some(Bool(false)));
+    assert_eq!(map.get("reset_dev_menu"), Some(Bool(false)));
+    assert_eq!(map.get("route"), Some(Choice("AllEmeralds")));
+    assert_eq!(map.get("studiopolis_2"), Some(Bool(true)));
+    assert_eq!(map.get("egg_reverie"), Some(Bool(true)));
+    assert_eq!(map.get("encore_green_hill_1"), Some(Bool(false)));
+    assert_eq!(map.get("chaos_emerald_1"), Some(Bool(true)));
+    assert_eq!(map.get("boss_green_hill"), Some(Bool(false)));
+    assert_eq!(map.get("star_posts_green_hill_1"), Some(Choice("None")));
+    // Every key written needs to be one the settings are stored with
+    assert!(map.keys().iter().all(|key| Settings::KEYS.contains(key)));
+
+    // Any% ends on Titanic Monarch, without going after the emeralds
+    let map = apply(Preset::ManiaAnyPercent);
+    assert_eq!(map.get("route"), Some(Choice("ManiaAnyPercent")));
+    assert_eq!(map.get("start_save_character"), Some(Choice("Any")));
+    assert_eq!(map.get("titanic_monarch_2"), Some(Bool(true)));
+    assert_eq!(map.get("egg_reverie"), Some(Bool(false)));
+    assert_eq!(map.get("chaos_emerald_1"), Some(Bool(false)));
+
+    assert!(apply(Preset::Custom).keys().is_empty());
+}
+
+#[test]
+fn knuckles_and_knuckles_preset_sets_the_character_and_route() {
+    use preset::Value::{Bool, Choice};
+
+    let map = apply(Preset::KnucklesAndKnuckles);
+    assert_eq!(map.get("route"), Some(Choice("KnucklesOnly")));
+    assert_eq!(map.get("start_save_character"), Some(Choice("Knuckles")));
+    assert_eq!(map.get("start_mania_mode"), Some(Bool(true)));
+    assert_eq!(map.get("start_encore_mode"), Some(Bool(false)));
+    assert_eq!(map.get("titanic_monarch_2"), Some(Bool(true)));
+    assert_eq!(map.get("encore_green_hill_1"), Some(Bool(false)));
+    assert_eq!(map.get("egg_reverie"), Some(Bool(false)));
+    assert!(map.keys().iter().all(|key| Settings::KEYS.contains(key)));
+}
+
+#[test]
+fn zone_and_mode_toggles_govern_their_acts() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100);
+
+    for settings in [
+        Settings {
+            mania_green_hill: false,
+            ..Settings::default()
+        },
+        Settings {
+            mania_mode: false,
+            ..Settings::default()
+        },
+    ] {
+        assert_eq!(replay(&recording.0, &settings), [(5, Action::Start)]);
+    }
+}
+
+#[test]
+fn any_order_route_splits_when_skipping_ahead() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .push(Snapshot::loading(MANIA, 13), 5);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(Snapshot::loading(MANIA, 13).with_status(2), 5)
+        .play(MANIA, 13, 0, 100);
+    let settings = Settings {
+        route: Route::AnyOrder,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start)]
+    );
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn any_order_route_does_not_split_when_going_back_to_a_played_act() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    let skip_tick = recording.tick() + 5;
+    let recording = recording
+        .load(MANIA, 13)
+        .play(MANIA, 13, 0, 300)
+        // Warping back to Green Hill Act 1 isn't progress, but moving on from it to a new act is
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    let split_tick = recording.tick() + 5;
+    let recording = recording.load(MANIA, 10).play(MANIA, 10, 0, 100);
+    let settings = Settings {
+        route: Route::AnyOrder,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [
+            (5, Action::Start),
+            (skip_tick, Action::Split),
+            (split_tick, Action::Split)
+        ]
+    );
+}
+
+#[test]
+fn category_routes_follow_mania_any_percent() {
+    for route in [Route::KnucklesOnly, Route::NoZips] {
+        assert!(route.expects(Acts::MirageSaloon1, Acts::MirageSaloon2));
+        assert!(!route.expects(Acts::MirageSaloon2, Acts::MirageSaloon1));
+        assert!(!route.contains(Acts::EncoreGreenHill1));
+        assert!(!route.ends_on_egg_reverie(true));
+        assert!(route.acts(false) == Route::ManiaAnyPercent.acts(false));
+    }
+}
+
+#[test]
+fn does_not_split_when_bouncing_back_to_the_previous_act() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    let split_tick = recording.tick() + 5;
+    let recording = recording
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100)
+        // The level ID flips back to Act 1 for a moment, then returns to Act 2
+        .load(MANIA, 9)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn undoes_false_splits() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    let false_split_tick = recording.tick() + 5;
+    let recording = recording.load(MANIA, 10);
+    let undo_tick = recording.tick() + 5;
+    let recording = recording.load(MANIA, 9).play(MANIA, 9, 0, 300);
+    let split_tick = recording.tick() + 5;
+    let recording = recording.load(MANIA, 10).play(MANIA, 10, 0, 100);
+
+    let settings = Settings {
+        undo_false_splits: true,
+        ..Settings::default()
+    };
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [
+            (5, Action::Start),
+            (false_split_tick, Action::Split),
+            (undo_tick, Action::Undo),
+            (split_tick, Action::Split)
+        ]
+    );
+}
+
+#[test]
+fn splits_on_act_clear() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    // The act timer needs to stay stopped for a while before the act counts as cleared
+    let split_tick = recording.tick() + ACT_CLEAR_TICKS as usize - 1;
+    let recording = recording
+        .results(MANIA, 9, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100);
+
+    let settings = Settings {
+        split_on_act_clear: true,
+        ..Settings::default()
+    };
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn dying_is_not_an_act_clear() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .die(MANIA, 9, 300)
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+
+    let settings = Settings {
+        split_on_act_clear: true,
+        ..Settings::default()
+    };
+    assert_eq!(replay(&recording.0, &settings), [(5, Action::Start)]);
+}
+
+#[test]
+fn practice_mode_starts_resets_and_splits_on_the_chosen_act() {
+    let recording = Recording::default().load(MANIA, 12).play(MANIA, 12, 0, 100);
+    // The act timer starts running from zero
+    let start_tick = recording.tick() + 11;
+    let recording = recording.load(MANIA, 13).play(MANIA, 13, 0, 300);
+    // Restarting the act brings the timer back to zero
+    let reset_tick = recording.tick();
+    let restart_tick = recording.tick() + 11;
+    let recording = recording.load(MANIA, 13).play(MANIA, 13, 0, 300);
+    let split_tick = recording.tick() + ACT_CLEAR_TICKS as usize - 1;
+    let recording = recording
+        .results(MANIA, 13, 300)
+        .load(MANIA, 14)
+        .play(MANIA, 14, 0, 100);
+
+    let settings = Settings {
+        practice_mode: true,
+        practice_act: Acts::Studiopolis1,
+        ..Settings::default()
+    };
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [
+            (start_tick, Action::Start),
+            (reset_tick, Action::Reset),
+            (restart_tick, Action::Start),
+            (split_tick, Action::Split)
+        ]
+    );
+}
+
+#[test]
+fn practice_mode_resets_without_splitting_when_dying_before_any_star_post() {
+    let recording = Recording::default();
+    let start_tick = recording.tick() + 11;
+    let recording = recording
+        .load(MANIA, 13)
+        .play(MANIA, 13, 0, 300)
+        .die(MANIA, 13, 300);
+    // Without a star post, the act gets reloaded with the timer back to zero
+    let reset_tick = recording.tick();
+    let restart_tick = recording.tick() + 11;
+    let recording = recording.load(MANIA, 13).play(MANIA, 13, 0, 100);
+
+    let settings = Settings {
+        practice_mode: true,
+        practice_act: Acts::Studiopolis1,
+        ..Settings::default()
+    };
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [
+            (start_tick, Action::Start),
+            (reset_tick, Action::Reset),
+            (restart_tick, Action::Start)
+        ]
+    );
+}
+
+#[test]
+fn splits_on_chaos_emeralds() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 65)
+        .play(MANIA, 65, 0, 100);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(
+            Snapshot {
+                chaos_emeralds: 0x01,
+                ..Snapshot::playing(MANIA, 65, 100)
+            },
+            30,
+        )
+        .push(
+            Snapshot {
+                chaos_emeralds: 0x01,
+                ..Snapshot::loading(MANIA, 9)
+            },
+            5,
+        );
+    let settings = Settings {
+        chaos_emerald_1: true,
+        special_stage_failed: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start)]
+    );
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_failed_special_stages() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 65)
+        .play(MANIA, 65, 0, 100);
+    let split_tick = recording.tick();
+    let recording = recording.push(Snapshot::loading(MANIA, 9), 5);
+    let settings = Settings {
+        special_stage_failed: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_blue_spheres_medals() {
+    let mut gold = [0; 32];
+    gold[0] = 2;
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 72)
+        .play(MANIA, 72, 0, 100);
+    let medal_tick = recording.tick();
+    let recording = recording
+        .push(
+            Snapshot {
+                medals: gold,
+                ..Snapshot::playing(MANIA, 72, 100)
+            },
+            30,
+        )
+        .push(
+            Snapshot {
+                medals: gold,
+                ..Snapshot::loading(MANIA, 9)
+            },
+            5,
+        );
+    let exit_tick = recording.tick() - 5;
+    let settings = Settings {
+        medal_earned: true,
+        bonus_stage_failed: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start)]
+    );
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (medal_tick, Action::Split)]
+    );
+    assert_eq!(
+        replay(
+            &recording.0,
+            &Settings {
+                bonus_stage_exited: true,
+                ..Settings::default()
+            }
+        ),
+        [(5, Action::Start), (exit_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_failed_bonus_stages() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 72)
+        .play(MANIA, 72, 0, 100);
+    let split_tick = recording.tick();
+    let recording = recording.push(Snapshot::loading(MANIA, 9), 5);
+    let settings = Settings {
+        medal_earned: true,
+        bonus_stage_failed: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_when_the_boss_is_defeated() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 300)
+        // Dying at the boss reloads the act without defeating it
+        .die(MANIA, 10, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 200);
+    let split_tick = recording.tick() + ACT_CLEAR_TICKS as usize - 1;
+    let recording = recording.results(MANIA, 10, 200).load(MANIA, 11);
+    let settings = Settings {
+        boss_green_hill: true,
+        green_hill_1: false,
+        green_hill_2: false,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_the_results_screen_where_it_can_be_read() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        // Game Over: the timer stays stopped for longer than it would on the results screen
+        .die(MANIA, 9, 300)
+        .push(Snapshot::playing(MANIA, 9, 300), ACT_CLEAR_TICKS as usize)
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 200);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(
+            Snapshot {
+                results_screen: Some(true),
+                ..Snapshot::playing(MANIA, 9, 200)
+            },
+            300,
+        )
+        .load(MANIA, 10)
+        .map(|snapshot| {
+            snapshot.results_screen.get_or_insert(false);
+        });
+    let settings = Settings {
+        split_on_act_clear: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_as_soon_as_the_boss_health_is_depleted() {
+    let fighting = |cs, health| Snapshot {
+        boss_health: Some(health),
+        ..Snapshot::playing(MANIA, 10, cs)
+    };
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 300)
+        .map(|snapshot| snapshot.boss_health = Some(8))
+        // Dying at the boss, which reads as defeated once the act reloads
+        .push(fighting(300, 3), 240)
+        .push(
+            Snapshot {
+                boss_health: Some(0),
+                ..Snapshot::loading(MANIA, 10)
+            },
+            5,
+        )
+        .push(fighting(0, 8), 1)
+        .push(fighting(1, 8), 1);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(fighting(1, 0), 30)
+        .results(MANIA, 10, 1)
+        .load(MANIA, 11);
+    let settings = Settings {
+        boss_green_hill: true,
+        green_hill_1: false,
+        green_hill_2: false,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn boss_and_act_clear_split_only_once() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 300);
+    let split_tick = recording.tick() + ACT_CLEAR_TICKS as usize - 1;
+    let recording = recording
+        .results(MANIA, 10, 300)
+        .load(MANIA, 11)
+        .play(MANIA, 11, 0, 100);
+    let settings = Settings {
+        boss_green_hill: true,
+        green_hill_1: false,
+        split_on_act_clear: true,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_star_posts() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 20)
+        .play(MANIA, 20, 0, 300);
+    let split_tick = recording.tick();
+    let recording = recording.push(
+        Snapshot {
+            star_post: 0x120,
+            ..Snapshot::playing(MANIA, 20, 301)
+        },
+        5,
+    );
+    let settings = Settings {
+        star_posts_stardust_speedway_2: StarPosts::Every,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start)]
+    );
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn splits_on_the_chosen_star_post_only() {
+    let star_post = |star_post, cs| Snapshot {
+        star_post,
+        ..Snapshot::playing(MANIA, 20, cs)
+    };
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 20)
+        .play(MANIA, 20, 0, 300)
+        .push(star_post(0x100, 301), 5)
+        .push(star_post(0x120, 306), 5)
+        // Going back to the 1st star post doesn't count as a new one
+        .push(star_post(0x100, 311), 5);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(star_post(0x140, 316), 5)
+        .push(star_post(0x160, 321), 5);
+    let settings = Settings {
+        star_posts_stardust_speedway_2: StarPosts::Third,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn logs_the_leader_clearing_every_act() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .results(MANIA, 9, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 300)
+        .results(MANIA, 10, 300)
+        // Swapping the leader for Knuckles in Act 2
+        .map(|snapshot| {
+            if snapshot.level_id == 10 {
+                snapshot.characters = [4, 2];
+            }
+        });
+
+    let mut watchers = Watchers::default();
+    let mut character_log = CharacterLog::default();
+    for snapshot in &recording.0 {
+        watchers.update(snapshot);
+        character_log.update(&watchers);
+    }
+
+    assert_eq!(
+        runtime::variable("Acts cleared by").as_deref(),
+        Some("Green Hill Act 1: Sonic, Green Hill Act 2: Knuckles")
+    );
+}
+
+#[test]
+fn counts_deaths_and_restarts() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        // Death without any star post
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .push(
+            Snapshot {
+                star_post: 0x120,
+                ..Snapshot::playing(MANIA, 9, 301)
+            },
+            5,
+        )
+        // Death after touching a star post, respawning with the time we had back then
+        .push(
+            Snapshot {
+                star_post: 0x120,
+                ..Snapshot::loading(MANIA, 9)
+            },
+            5,
+        )
+        .push(
+            Snapshot {
+                star_post: 0x120,
+                ..Snapshot::playing(MANIA, 9, 250)
+            },
+            5,
+        )
+        // Restart from the pause menu, before and after touching a star post
+        .restart(MANIA, 9, 250)
+        .play(MANIA, 9, 0, 300)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100)
+        .restart(MANIA, 10, 100)
+        .play(MANIA, 10, 0, 300);
+
+    let mut watchers = Watchers::default();
+    let mut death_counter = DeathCounter::default();
+    for snapshot in &recording.0 {
+        watchers.update(snapshot);
+        death_counter.update(&watchers);
+    }
+
+    assert_eq!(death_counter.deaths, 2);
+    assert_eq!(death_counter.restarts, 2);
+    assert_eq!(death_counter.deaths_in_act, 0);
+    assert_eq!(death_counter.deaths_per_act.as_str(), "Green Hill Act 1: 2");
+    assert_eq!(runtime::variable("Deaths").as_deref(), Some("2"));
+    assert_eq!(runtime::variable("Restarts").as_deref(), Some("2"));
+}
+
+#[test]
+fn checks_enabled_splits_against_the_splits_file() {
+    let mut settings = Settings {
+        boss_green_hill: true,
+        ..Settings::default()
+    };
+
+    // Every Encore mode act, plus the boss
+    let mut check = SplitCheck::count(&settings, true);
+    assert_eq!(check.expected, 26);
+    assert!(check.exact);
+
+    // The timer ending early means there aren't enough segments
+    for _ in 0..25 {
+        check.split();
+    }
+    check.update(TimerState::Ended);
+    assert!(check.warned);
+    assert_eq!(
+        runtime::messages(),
+        ["The splits file has fewer segments than the splits enabled in the settings"]
+    );
+
+    // When splitting on act clear, the boss split replaces the split of its act
+    settings.split_on_act_clear = true;
+    let check = SplitCheck::count(&settings, true);
+    assert_eq!(check.expected, 25);
+    assert!(check.exact);
+    settings.split_on_act_clear = false;
+
+    // Star posts make the number of splits depend on the run, so only missing segments can be detected
+    settings.star_posts_green_hill_1 = StarPosts::Every;
+    let mut check = SplitCheck::count(&settings, true);
+    assert!(!check.exact);
+    for _ in 0..26 {
+        check.split();
+    }
+    check.update(TimerState::Running);
+    assert!(!check.warned);
+}
+
+#[test]
+fn checks_the_segments_entered_in_the_settings() {
+    let settings = Settings {
+        boss_green_hill: true,
+        ..Settings::default()
+    };
+
+    // Every Encore mode act, plus the boss
+    let mut check = SplitCheck::count(&settings, true);
+    check.check_segments(Segments::S26);
+    assert!(!check.warned);
+    check.check_segments(Segments::S27);
+    assert!(check.warned);
+
+    // Star posts make the number of splits depend on the run, so only missing segments can be detected
+    let settings = Settings {
+        star_posts_green_hill_1: StarPosts::Every,
+        ..settings
+    };
+    let mut check = SplitCheck::count(&settings, true);
+    check.check_segments(Segments::S30);
+    assert!(!check.warned);
+    check.check_segments(Segments::S25);
+    assert!(check.warned);
+
+    assert_eq!(
+        runtime::messages(),
+        [
+            "The splits file has more segments than the splits enabled in the settings",
+            "The splits file has fewer segments than the splits enabled in the settings"
+        ]
+    );
+}
+
+#[test]
+fn names_acts_for_display() {
+    assert_eq!(&Acts::GreenHill1.name(), "Green Hill Act 1");
+    assert_eq!(
+        &Acts::EncoreStudiopolis2.name(),
+        "Studiopolis Act 2 (Encore)"
+    );
+    assert_eq!(&Acts::EncoreAngelIsland.name(), "Angel Island (Encore)");
+    assert_eq!(&Acts::EggReverie.name(), "Egg Reverie");
+}
+
+#[test]
+fn titanic_monarch_2_with_all_emeralds_splits_on_egg_reverie() {
+    // Defeating the boss in Titanic Monarch Act 2 is not the end of the run if
+    // Sonic has collected all the Chaos Emeralds, so we must not split early
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 35)
+        .play(MANIA, 35, 0, 300)
+        .push(
+            Snapshot {
+                tm2_defeated: 1,
+                ..Snapshot::playing(MANIA, 35, 300)
+            },
+            30,
+        );
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(Snapshot::loading(MANIA, 37), 5)
+        .map(|snapshot| snapshot.chaos_emeralds = 0x7F);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn any_percent_route_ends_on_titanic_monarch_2() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 35)
+        .play(MANIA, 35, 0, 300);
+    let split_tick = recording.tick();
+    let recording = recording
+        .push(
+            Snapshot {
+                tm2_defeated: 1,
+                ..Snapshot::playing(MANIA, 35, 300)
+            },
+            30,
+        )
+        .push(Snapshot::loading(MANIA, 37), 5)
+        .map(|snapshot| snapshot.chaos_emeralds = 0x7F);
+    let settings = Settings {
+        route: Route::ManiaAnyPercent,
+        ..Settings::default()
+    };
+
+    assert_eq!(
+        replay(&recording.0, &settings),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn titanic_monarch_2_without_all_emeralds_splits_on_boss_defeat() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 35)
+        .play(MANIA, 35, 0, 300);
+    let split_tick = recording.tick();
+    let recording = recording.push(
+        Snapshot {
+            tm2_defeated: 1,
+            ..Snapshot::playing(MANIA, 35, 300)
+        },
+        30,
+    );
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn egg_reverie_splits_when_both_bosses_are_defeated() {
+    let egg_reverie = |monarch, eggman| Snapshot {
+        egg_reverie_monarch_health: monarch,
+        egg_reverie_eggman_health: eggman,
+        chaos_emeralds: 0x7F,
+        ..Snapshot::playing(MANIA, 37, 4242)
+    };
+    let recording = Recording::default()
+        .new_game()
+        .push(egg_reverie(8, 8), 10)
+        .push(egg_reverie(0, 8), 10)
+        .push(egg_reverie(0, 1), 10);
+    let split_tick = recording.tick();
+    let recording = recording.push(egg_reverie(0, 0), 10);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn resets_when_returning_to_the_main_menu() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300);
+    let reset_tick = recording.tick();
+    let recording = recording.push(Snapshot::main_menu(), 10);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (reset_tick, Action::Reset)]
+    );
+}
+
+#[test]
+fn encore_splits_from_angel_island() {
+    let recording = Recording::default()
+        .new_game()
+        .map(|snapshot| {
+            snapshot.game_mode = ENCORE;
+            snapshot.mania_mode_selection = 0;
+            snapshot.save_selection_encore = 0;
+        })
+        .load(ENCORE, 118)
+        .play(ENCORE, 118, 0, 300)
+        .push(Snapshot::loading(ENCORE, 38), 5);
+    let split_tick = recording.tick();
+    let recording = recording.push(Snapshot::loading(ENCORE, 38).with_status(2), 5);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [(5, Action::Start), (split_tick, Action::Split)]
+    );
+}
+
+#[test]
+fn time_attack_starts_splits_and_resets() {
+    let recording = Recording::default()
+        .load(TIME_ATTACK, 13)
+        .push(Snapshot::playing(TIME_ATTACK, 13, 0), 20);
+    let start_tick = recording.tick();
+    let recording = recording.play(TIME_ATTACK, 13, 1, 500);
+    let split_tick = recording.tick() + ACT_CLEAR_TICKS as usize - 1;
+    let recording = recording.results(TIME_ATTACK, 13, 500);
+    let reset_tick = recording.tick();
+    let recording = recording.load(TIME_ATTACK, 13);
+
+    assert_eq!(
+        replay(&recording.0, &Settings::default()),
+        [
+            (start_tick, Action::Start),
+            (split_tick, Action::Split),
+            (reset_tick, Action::Reset),
+        ]
+    );
+}
+
+#[test]
+fn traces_replay_like_the_original_recording() {
+    let recording = Recording::default()
+        .new_game()
+        .load(MANIA, 9)
+        .play(MANIA, 9, 0, 300)
+        .push(Snapshot::playing(MANIA, 9, 300), 30)
+        .load(MANIA, 10)
+        .play(MANIA, 10, 0, 100);
+
+    // Only print the values when they change, as the recorder does, and add some noise
+    // similar to what could be found when copying the trace from the log
+    let mut text = String::from("Loaded script\n");
+    for (tick, snapshot) in recording.0.iter().enumerate() {
+        if tick == 0 || recording.0[tick - 1] != *snapshot {
+            text.push_str("[Autosplitter] ");
+            text.push_str(&trace::encode(tick as u32, snapshot).unwrap());
+            text.push('\n');
+        }
+    }
+
+    let snapshots = load_trace(&text);
+    assert!(snapshots == recording.0);
+    assert_eq!(
+        replay(&snapshots, &Settings::default()),
+        replay(&recording.0, &Settings::default())
+    );
+}
+
+#[test]
+fn the_widest_trace_line_fits() {
+    let snapshot = Snapshot {
+        game_mode: u8::MAX,
+        level_id: u8::MAX,
+        status: [u8::MAX; 5],
+        egg_reverie_monarch_health: u8::MAX,
+        egg_reverie_eggman_health: u8::MAX,
+        tm2_defeated: u8::MAX,
+        boss_health: Some(u8::MAX),
+        results_screen: Some(true),
+        chaos_emeralds: u8::MAX,
+        characters: [u8::MAX; 2],
+        stock: [u8::MAX; 4],
+        start_trigger: u32::MAX,
+        mania_mode_selection: u8::MAX,
+        save_selection_mania: u8::MAX,
+        saves_mania: [u8::MAX; 8],
+        save_selection_encore: u8::MAX,
+        saves_encore: [u8::MAX; 3],
+        medals: [u8::MAX; 32],
+        star_post: u32::MAX,
+        save_progress: [u8::MAX; 3],
+        save_team: [u8::MAX; 2],
+    };
+    let line = trace::encode(u32::MAX, &snapshot).unwrap();
+    assert!(trace::parse_line(&line) == Some((u32::MAX, snapshot)));
+}
@@ -0,0 +1,193 @@
+//! Compact, line-based trace of the raw values read from the game's memory.
+//!
+//! When enabled in the settings, a new line is printed through `asr::print_message` every time any
+//! of the values changes, so the trace can be copied from the log and attached to a bug report.
+//! The same lines can be fed to the replay tests as they are: anything before the `SMTRACE` marker
+//! (eg. timestamps added by the log) and any unrelated line get ignored.
+//!
+//! ```text
+//! SMTRACE 1f4 00 09 0100171600 000000 7f 0102 00000000 00000030 0108 ffffffffffffffff 00 000000 0102000000000000000000000000000000000000000000000000000000000000 00000000 010203 -- -- 0000
+//! ```
+//!
+//! Every field is in hexadecimal and they are, in order: tick, game mode, level ID, status bytes,
+//! boss values (Egg Reverie's Phantom King health, Egg Reverie's Eggman health, Titanic Monarch
+//! Act 2 defeat flag), chaos emeralds, characters, stock, start trigger, Mania mode and Mania save
+//! selection, Mania save slots, Encore save selection, Encore save slots, Blue Spheres medals, last
+//! star post, progress of the selected save slot (state, character, zone), health of the Act 2 boss
+//! and results screen flag (`--` when they can't be read), team of the selected Encore save slot.
+
+use crate::{runtime, Snapshot};
+use arrayvec::ArrayString;
+use core::fmt::Write;
+
+const MARKER: &str = "SMTRACE";
+
+type Line = ArrayString<192>;
+
+#[derive(Default)]
+pub struct Recorder {
+    tick: u32,
+    last: Option<Snapshot>,
+}
+
+impl Recorder {
+    /// Prints the snapshot if it differs from the last one we recorded
+    pub fn record(&mut self, snapshot: &Snapshot) {
+        if self.last.as_ref() != Some(snapshot) {
+            // A line that doesn't fit gets dropped rather than printed with fields missing
+            if let Some(line) = encode(self.tick, snapshot) {
+                runtime::print_message(&line);
+            }
+            self.last = Some(*snapshot);
+        }
+        self.tick = self.tick.wrapping_add(1);
+    }
+}
+
+/// Encodes the snapshot as a line of the trace, or returns `None` if it doesn't fit in a line
+pub fn encode(tick: u32, snapshot: &Snapshot) -> Option<Line> {
+    let mut line = Line::new();
+    write!(
+        line,
+        "{MARKER} {tick:x} {:02x} {:02x} ",
+        snapshot.game_mode, snapshot.level_id
+    )
+    .ok()?;
+    push_hex(&mut line, &snapshot.status)?;
+    line.try_push(' ').ok()?;
+    push_hex(
+        &mut line,
+        &[
+            snapshot.egg_reverie_monarch_health,
+            snapshot.egg_reverie_eggman_health,
+            snapshot.tm2_defeated,
+        ],
+    )?;
+    write!(line, " {:02x} ", snapshot.chaos_emeralds).ok()?;
+    push_hex(&mut line, &snapshot.characters)?;
+    line.try_push(' ').ok()?;
+    push_hex(&mut line, &snapshot.stock)?;
+    write!(
+        line,
+        " {:08x} {:02x}{:02x} ",
+        snapshot.start_trigger, snapshot.mania_mode_selection, snapshot.save_selection_mania
+    )
+    .ok()?;
+    push_hex(&mut line, &snapshot.saves_mania)?;
+    write!(line, " {:02x} ", snapshot.save_selection_encore).ok()?;
+    push_hex(&mut line, &snapshot.saves_encore)?;
+    line.try_push(' ').ok()?;
+    push_hex(&mut line, &snapshot.medals)?;
+    write!(line, " {:08x} ", snapshot.star_post).ok()?;
+    push_hex(&mut line, &snapshot.save_progress)?;
+    push_optional_hex(&mut line, snapshot.boss_health)?;
+    push_optional_hex(&mut line, snapshot.results_screen.map(u8::from))?;
+    line.try_push(' ').ok()?;
+    push_hex(&mut line, &snapshot.save_team)?;
+    Some(line)
+}
+
+fn push_optional_hex(line: &mut Line, byte: Option<u8>) -> Option<()> {
+    match byte {
+        Some(byte) => write!(line, " {byte:02x}"),
+        None => write!(line, " --"),
+    }
+    .ok()
+}
+
+fn push_hex(line: &mut Line, bytes: &[u8]) -> Option<()> {
+    for byte in bytes {
+        write!(line, "{byte:02x}").ok()?;
+    }
+    Some(())
+}
+
+/// Parses a single line of the trace, returning the tick it was recorded at together with the
+/// snapshot
+#[cfg(test)]
+pub fn parse_line(line: &str) -> Option<(u32, Snapshot)> {
+    let (_, line) = line.split_once(MARKER)?;
+    let mut fields = line.split_whitespace();
+
+    let tick = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let [game_mode] = parse_hex(fields.next()?)?;
+    let [level_id] = parse_hex(fields.next()?)?;
+    let status = parse_hex(fields.next()?)?;
+    let [egg_reverie_monarch_health, egg_reverie_eggman_health, tm2_defeated] =
+        parse_hex(fields.next()?)?;
+    let [chaos_emeralds] = parse_hex(fields.next()?)?;
+    let characters = parse_hex(fields.next()?)?;
+    let stock = parse_hex(fields.next()?)?;
+    let start_trigger = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let [mania_mode_selection, save_selection_mania] = parse_hex(fields.next()?)?;
+    let saves_mania = parse_hex(fields.next()?)?;
+    let [save_selection_encore] = parse_hex(fields.next()?)?;
+    let saves_encore = parse_hex(fields.next()?)?;
+    // Fields added after the first version of the format, missing from older traces
+    let medals = match fields.next() {
+        Some(field) => parse_hex(field)?,
+        None => Default::default(),
+    };
+    let star_post = match fields.next() {
+        Some(field) => u32::from_str_radix(field, 16).ok()?,
+        None => 0,
+    };
+    let save_progress = match fields.next() {
+        Some(field) => parse_hex(field)?,
+        None => Default::default(),
+    };
+    let boss_health = parse_optional_hex(fields.next())?;
+    let results_screen = parse_optional_hex(fields.next())?.map(|flag| flag != 0);
+    let save_team = match fields.next() {
+        Some(field) => parse_hex(field)?,
+        None => Default::default(),
+    };
+
+    Some((
+        tick,
+        Snapshot {
+            game_mode,
+            level_id,
+            status,
+            egg_reverie_monarch_health,
+            egg_reverie_eggman_health,
+            tm2_defeated,
+            boss_health,
+            results_screen,
+            chaos_emeralds,
+            characters,
+            stock,
+            start_trigger,
+            mania_mode_selection,
+            save_selection_mania,
+            saves_mania,
+            save_selection_encore,
+            saves_encore,
+            medals,
+            star_post,
+            save_progress,
+            save_team,
+        },
+    ))
+}
+
+/// Parses a field which can be missing, either from older traces or because its value couldn't be read
+#[cfg(test)]
+fn parse_optional_hex(field: Option<&str>) -> Option<Option<u8>> {
+    match field {
+        Some("--") | None => Some(None),
+        Some(field) => parse_hex(field).map(|[byte]| Some(byte)),
+    }
+}
+
+#[cfg(test)]
+fn parse_hex<const N: usize>(field: &str) -> Option<[u8; N]> {
+    if field.len() != N * 2 {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(field.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}